@@ -0,0 +1,222 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::api::{
+    error::Result,
+    limiter::{Budget, RateLimits, ThrottleHook},
+    readwise::{RateLimitPolicy, ReadwiseClient, ReadwiseConfig, RetryPolicy},
+    token::{self, TokenSource},
+    transport::{Transport, UreqTransport},
+};
+use crate::config::Profile;
+use asimov_module::secrecy::SecretString;
+use std::{path::PathBuf, time::Duration};
+
+/// Configures a [`ReadwiseClient`] in one place, validating the settings
+/// when the client is built.
+///
+/// ```no_run
+/// # use asimov_readwise_module::api::readwise::ReadwiseClient;
+/// # use std::time::Duration;
+/// let client = ReadwiseClient::builder()
+///     .access_token("my-token")
+///     .read_timeout(Duration::from_secs(30))
+///     .page_size(500)
+///     .build()?;
+/// # Ok::<(), asimov_readwise_module::api::ReadwiseError>(())
+/// ```
+pub struct ReadwiseClientBuilder {
+    config: ReadwiseConfig,
+    token: Vec<TokenSource>,
+    transport: Option<Box<dyn Transport>>,
+}
+
+impl Default for ReadwiseClientBuilder {
+    fn default() -> Self {
+        Self {
+            config: ReadwiseConfig::new(SecretString::default()),
+            token: Vec::new(),
+            transport: None,
+        }
+    }
+}
+
+impl ReadwiseClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.config.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the base URL of the Reader API, which is otherwise derived from
+    /// the base URL.
+    pub fn reader_base_url(mut self, reader_base_url: impl Into<String>) -> Self {
+        self.config.reader_base_url =
+            Some(reader_base_url.into().trim_end_matches('/').to_string());
+        self
+    }
+
+    pub fn access_token(self, token: impl Into<SecretString>) -> Self {
+        self.token_source(TokenSource::Value(token.into()))
+    }
+
+    /// Sets a token already resolved from `source`, which errors name when
+    /// Readwise rejects the token.
+    pub fn resolved_token(mut self, token: SecretString, source: &TokenSource) -> Self {
+        self.config.token_source = Some(source.to_string());
+        self.access_token(token)
+    }
+
+    pub fn token_source(self, source: TokenSource) -> Self {
+        self.token_sources(vec![source])
+    }
+
+    /// Sets the token sources to try in order of precedence; the first one
+    /// providing a token wins.
+    pub fn token_sources(mut self, sources: Vec<TokenSource>) -> Self {
+        self.token = sources;
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.config.connect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.config.read_timeout = Some(timeout);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.config.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.config.proxy = Some(proxy.into());
+        self
+    }
+
+    pub fn ca_bundle(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.ca_bundle = Some(path.into());
+        self
+    }
+
+    /// Sets the largest response body to read, in bytes.
+    pub fn max_body_size(mut self, max_body_size: u64) -> Self {
+        self.config.max_body_size = max_body_size;
+        self
+    }
+
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.config.retry = retry;
+        self
+    }
+
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.config.retry.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn max_retry_wait(mut self, max_wait: Duration) -> Self {
+        self.config.retry.max_wait = max_wait;
+        self
+    }
+
+    pub fn rate_limit_policy(mut self, policy: RateLimitPolicy) -> Self {
+        self.config.rate_limit = policy;
+        self
+    }
+
+    /// Replaces the client-side request budgets per endpoint.
+    pub fn rate_limits(mut self, limits: RateLimits) -> Self {
+        self.config.rate_limits = limits;
+        self
+    }
+
+    /// Sets the request budget of one endpoint, e.g. `/highlights/`, or of
+    /// all other endpoints with [`DEFAULT_ENDPOINT`](crate::api::limiter::DEFAULT_ENDPOINT).
+    pub fn rate_limit(mut self, endpoint: &str, budget: Budget) -> Self {
+        self.config.rate_limits.set(endpoint, budget);
+        self
+    }
+
+    /// Calls `hook` with the endpoint and its budget the first time requests
+    /// to an endpoint are throttled.
+    pub fn on_throttle(mut self, hook: impl Fn(&str, Budget) + Send + Sync + 'static) -> Self {
+        self.config.on_throttle = Some(ThrottleHook::new(hook));
+        self
+    }
+
+    /// Sets the page size used when a fetch call does not specify one.
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.config.page_size = Some(page_size);
+        self
+    }
+
+    /// Applies the settings of a config file profile, except its token
+    /// sources.
+    pub fn profile(mut self, profile: &Profile) -> Self {
+        if let Some(base_url) = &profile.base_url {
+            self = self.base_url(base_url);
+        }
+        if let Some(page_size) = profile.page_size {
+            self = self.page_size(page_size);
+        }
+        if let Some(timeout) = profile.connect_timeout {
+            self = self.connect_timeout(Duration::from_secs(timeout));
+        }
+        if let Some(timeout) = profile.read_timeout {
+            self = self.read_timeout(Duration::from_secs(timeout));
+        }
+        if let Some(proxy) = &profile.proxy {
+            self = self.proxy(proxy);
+        }
+        if let Some(ca_bundle) = &profile.ca_bundle {
+            self = self.ca_bundle(ca_bundle);
+        }
+        if let Some(max_attempts) = profile.max_attempts {
+            self = self.max_attempts(max_attempts);
+        }
+        if let Some(max_wait) = profile.max_retry_wait {
+            self = self.max_retry_wait(Duration::from_secs(max_wait));
+        }
+        for (endpoint, requests) in &profile.rate_limits {
+            self = self.rate_limit(endpoint, Budget::per_minute(*requests));
+        }
+        self
+    }
+
+    /// Sends requests through the given transport instead of `ureq`.
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(Box::new(transport));
+        self
+    }
+
+    /// Resolves the token and validates the settings, without creating a
+    /// client.
+    pub fn build_config(&self) -> Result<ReadwiseConfig> {
+        let mut config = self.config.clone();
+        if !self.token.is_empty() {
+            let (token, source) = token::find_first(&self.token)?;
+            config.access_token = token;
+            if !matches!(source, TokenSource::Value(_)) {
+                config.token_source = Some(source.to_string());
+            }
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn build(self) -> Result<ReadwiseClient> {
+        let config = self.build_config()?;
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => Box::new(UreqTransport::new(&config)?),
+        };
+        ReadwiseClient::with_transport(config, transport)
+    }
+}
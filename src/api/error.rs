@@ -0,0 +1,116 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::api::transport::TransportError;
+use std::{path::PathBuf, time::Duration};
+
+pub type Result<T, E = ReadwiseError> = std::result::Result<T, E>;
+
+/// An error returned by the Readwise API client.
+#[derive(Debug, thiserror::Error)]
+pub enum ReadwiseError {
+    #[error("invalid Readwise client configuration: {0}")]
+    Config(String),
+
+    #[error(
+        "Readwise rejected the access token{} (HTTP {status})",
+        token_source_hint(.token_source)
+    )]
+    Unauthorized {
+        status: u16,
+        /// Where the token came from, e.g. `$READWISE_API_KEY`, if known.
+        token_source: Option<String>,
+    },
+
+    #[error("Rate limit exceeded (429).{}", retry_after_hint(.retry_after))]
+    RateLimited { retry_after: Option<Duration> },
+
+    #[error("Readwise resource not found (404): {url}")]
+    NotFound { url: String },
+
+    #[error("HTTP status {status} from Readwise: {body}")]
+    Http { status: u16, body: String },
+
+    #[error("failed to reach Readwise: {0}")]
+    Transport(#[from] TransportError),
+
+    #[error("fixture {}: {source}", path.display())]
+    Fixture {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("failed to decode response from {endpoint}: {source}")]
+    Decode {
+        endpoint: String,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+fn token_source_hint(token_source: &Option<String>) -> String {
+    match token_source {
+        Some(source) => format!(" from {}", source),
+        None => String::new(),
+    }
+}
+
+fn retry_after_hint(retry_after: &Option<Duration>) -> String {
+    match retry_after {
+        Some(wait) => format!(" Readwise asked to retry after {}s.", wait.as_secs()),
+        None => " Please wait a minute before trying again.".to_string(),
+    }
+}
+
+impl ReadwiseError {
+    /// A stable, machine-readable name for the error class.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ReadwiseError::Config(_) => "config",
+            ReadwiseError::Unauthorized { .. } => "unauthorized",
+            ReadwiseError::RateLimited { .. } => "rate_limited",
+            ReadwiseError::NotFound { .. } => "not_found",
+            ReadwiseError::Http { .. } => "http",
+            ReadwiseError::Transport(_) => "transport",
+            ReadwiseError::Fixture { .. } => "fixture",
+            ReadwiseError::Decode { .. } => "decode",
+        }
+    }
+
+    /// Whether retrying the same request later may succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ReadwiseError::RateLimited { .. } => true,
+            ReadwiseError::Http { status, .. } => *status >= 500,
+            _ => self.is_transient(),
+        }
+    }
+
+    /// Whether a failure to get a response at all is worth retrying, as
+    /// with a dropped connection, unlike e.g. a TLS or body size error.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, ReadwiseError::Transport(error) if error.is_transient())
+    }
+}
+
+#[cfg(feature = "cli")]
+impl From<&ReadwiseError> for clientele::SysexitsError {
+    fn from(error: &ReadwiseError) -> Self {
+        use clientele::SysexitsError::*;
+        match error {
+            ReadwiseError::Config(_) => EX_CONFIG,
+            ReadwiseError::Unauthorized { .. } => EX_NOPERM,
+            ReadwiseError::RateLimited { .. } => EX_TEMPFAIL,
+            ReadwiseError::NotFound { .. } => EX_NOINPUT,
+            ReadwiseError::Http { status, .. } if *status >= 500 => EX_UNAVAILABLE,
+            ReadwiseError::Http { .. } => EX_DATAERR,
+            ReadwiseError::Transport(_) => EX_UNAVAILABLE,
+            ReadwiseError::Fixture { source, .. } => match source.kind() {
+                std::io::ErrorKind::NotFound => EX_NOINPUT,
+                std::io::ErrorKind::InvalidData => EX_DATAERR,
+                _ => EX_IOERR,
+            },
+            ReadwiseError::Decode { .. } => EX_DATAERR,
+        }
+    }
+}
@@ -0,0 +1,268 @@
+// This is free and unencumbered software released into the public domain.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// The budget key of endpoints without a budget of their own.
+pub const DEFAULT_ENDPOINT: &str = "default";
+
+/// A request budget: `requests` per `per`, with up to `burst` requests sent
+/// back to back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Budget {
+    pub requests: u32,
+    pub per: Duration,
+    pub burst: u32,
+}
+
+impl Budget {
+    /// A budget of `requests` per minute, spread evenly over the minute.
+    pub const fn per_minute(requests: u32) -> Self {
+        Self {
+            requests,
+            per: Duration::from_secs(60),
+            burst: 1,
+        }
+    }
+
+    pub const fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+
+    /// The number of requests regained per second.
+    fn rate(&self) -> f64 {
+        f64::from(self.requests) / self.per.as_secs_f64()
+    }
+}
+
+impl std::fmt::Display for Budget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.per.as_secs() {
+            60 => write!(f, "{} per minute", self.requests),
+            secs => write!(f, "{} per {}s", self.requests, secs),
+        }
+    }
+}
+
+/// The request budgets per endpoint, keyed by the trailing segments of the
+/// endpoint path, such as `/highlights/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimits {
+    /// The budget of every endpoint not listed in `endpoints`, if limited.
+    pub default: Option<Budget>,
+    pub endpoints: BTreeMap<String, Budget>,
+}
+
+impl Default for RateLimits {
+    /// The limits Readwise documents: 20 requests per minute on the
+    /// highlight, book and export lists, and 240 per minute elsewhere.
+    fn default() -> Self {
+        Self {
+            default: Some(Budget::per_minute(240)),
+            endpoints: [
+                ("/highlights/", Budget::per_minute(20)),
+                ("/books/", Budget::per_minute(20)),
+                ("/export/", Budget::per_minute(20)),
+                ("/list/", Budget::per_minute(20)),
+            ]
+            .into_iter()
+            .map(|(path, budget)| (path.to_string(), budget))
+            .collect(),
+        }
+    }
+}
+
+impl RateLimits {
+    /// No client-side limits; requests are only paced by 429 responses.
+    pub fn unlimited() -> Self {
+        Self {
+            default: None,
+            endpoints: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the budget of an endpoint, or with [`DEFAULT_ENDPOINT`] of all
+    /// other endpoints. The endpoint is normalized with [`endpoint_key`].
+    pub fn set(&mut self, endpoint: &str, budget: Budget) {
+        match endpoint_key(endpoint) {
+            key if key == DEFAULT_ENDPOINT => self.default = Some(budget),
+            key => {
+                self.endpoints.insert(key, budget);
+            },
+        }
+    }
+
+    /// Returns the budget key and budget for a request URL. Query strings
+    /// are ignored, so all pages of a list share one budget.
+    pub fn budget_for(&self, url: &str) -> (&str, Option<Budget>) {
+        let path = match url::Url::parse(url) {
+            Ok(url) => url.path().to_string(),
+            Err(_) => url.split('?').next().unwrap_or_default().to_string(),
+        };
+        self.endpoints
+            .iter()
+            .find(|(endpoint, _)| path.ends_with(endpoint.as_str()))
+            .map(|(endpoint, budget)| (endpoint.as_str(), Some(*budget)))
+            .unwrap_or((DEFAULT_ENDPOINT, self.default))
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        for budget in self.default.iter().chain(self.endpoints.values()) {
+            if budget.requests == 0 || budget.per.is_zero() || budget.burst == 0 {
+                return Err(format!("invalid rate limit: {}", budget));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the budget key of an endpoint, which is either [`DEFAULT_ENDPOINT`]
+/// or a path enclosed in slashes, e.g. `/highlights/` for `highlights` or
+/// `/highlights`. Keys match the end of request paths, so without the
+/// slashes they would match no endpoint at all, or the wrong one.
+pub fn endpoint_key(endpoint: &str) -> String {
+    match endpoint.trim() {
+        DEFAULT_ENDPOINT => DEFAULT_ENDPOINT.to_string(),
+        path => format!("/{}/", path.trim_matches('/')),
+    }
+}
+
+/// Parses a `--rate-limit` setting, `ENDPOINT=REQUESTS` in requests per
+/// minute, e.g. `/highlights/=20` or `default=240`.
+pub fn parse_rate_limit(input: &str) -> Result<(String, Budget), String> {
+    let Some((endpoint, requests)) = input.split_once('=') else {
+        return Err(format!("expected ENDPOINT=REQUESTS, got `{}`", input));
+    };
+    if endpoint.trim().trim_matches('/').is_empty() {
+        return Err(format!(
+            "expected an endpoint such as `/highlights/`, got `{}`",
+            endpoint
+        ));
+    }
+    let requests: u32 = requests
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid number of requests per minute: `{}`", requests))?;
+    if requests == 0 {
+        return Err("the number of requests per minute must be positive".to_string());
+    }
+    Ok((endpoint_key(endpoint), Budget::per_minute(requests)))
+}
+
+/// A callback notified the first time requests to an endpoint are
+/// throttled, with the endpoint's budget key and budget, e.g. to tell the
+/// user why an import slowed down.
+#[derive(Clone)]
+pub struct ThrottleHook(Arc<ThrottleFn>);
+
+type ThrottleFn = dyn Fn(&str, Budget) + Send + Sync;
+
+impl ThrottleHook {
+    pub fn new(hook: impl Fn(&str, Budget) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(hook))
+    }
+}
+
+impl std::fmt::Debug for ThrottleHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ThrottleHook")
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+    throttled: bool,
+}
+
+/// A token-bucket limiter pacing requests to the budget of their endpoint.
+///
+/// Buckets are shared by every request to the same endpoint, so paginated
+/// loops and repeated lookups draw from one budget.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limits: RateLimits,
+    buckets: Mutex<HashMap<String, Bucket>>,
+    on_throttle: Option<ThrottleHook>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            limits,
+            buckets: Mutex::new(HashMap::new()),
+            on_throttle: None,
+        }
+    }
+
+    /// Notifies `hook` the first time requests to an endpoint are throttled.
+    pub fn with_throttle_hook(mut self, hook: ThrottleHook) -> Self {
+        self.on_throttle = Some(hook);
+        self
+    }
+
+    pub fn limits(&self) -> &RateLimits {
+        &self.limits
+    }
+
+    /// Blocks until a request to `url` fits its budget, and returns how
+    /// long it waited.
+    pub fn acquire(&self, url: &str) -> Duration {
+        let wait = self.reserve(url);
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+        wait
+    }
+
+    /// Takes a request from the budget of `url` without blocking, and
+    /// returns how long the caller must wait before sending it.
+    pub fn reserve(&self, url: &str) -> Duration {
+        let (endpoint, budget) = self.limits.budget_for(url);
+        let Some(budget) = budget else {
+            return Duration::ZERO;
+        };
+
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(endpoint.to_string()).or_insert(Bucket {
+            tokens: f64::from(budget.burst),
+            updated_at: now,
+            throttled: false,
+        });
+
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * budget.rate()).min(f64::from(budget.burst));
+        bucket.updated_at = now;
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            return Duration::ZERO;
+        }
+
+        let wait = Duration::from_secs_f64(-bucket.tokens / budget.rate());
+        if !bucket.throttled {
+            bucket.throttled = true;
+            #[cfg(feature = "tracing")]
+            asimov_module::tracing::warn!(
+                "throttling requests to {} to {} to stay within Readwise's rate limits",
+                endpoint,
+                budget
+            );
+            if let Some(ThrottleHook(hook)) = &self.on_throttle {
+                hook(endpoint, budget);
+            }
+        }
+        #[cfg(feature = "tracing")]
+        asimov_module::tracing::debug!(
+            "waiting {:.1}s before requesting {}",
+            wait.as_secs_f32(),
+            url
+        );
+        wait
+    }
+}
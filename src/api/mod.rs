@@ -1,2 +1,11 @@
+pub mod builder;
+pub mod error;
+pub mod filter;
+pub mod limiter;
 pub mod readwise;
+pub mod recording;
+pub mod token;
+pub mod transport;
 pub mod types;
+
+pub use error::ReadwiseError;
@@ -1,40 +1,224 @@
 // This is free and unencumbered software released into the public domain.
 
-use crate::api::types::{BookListResponse, HighlightsResponse};
-use anyhow::Result;
+use crate::api::builder::ReadwiseClientBuilder;
+use crate::api::error::{ReadwiseError, Result};
+use crate::api::filter::{BookFilter, DocumentFilter, HighlightFilter};
+use crate::api::limiter::{RateLimiter, RateLimits, ThrottleHook};
+use crate::api::transport::{HttpRequest, HttpResponse, Transport, UreqTransport};
+use crate::api::types::{
+    AuthStatus, Book, BookDetail, BookListResponse, DailyReview, ExportBook, ExportResponse,
+    ExportTag, Highlight, HighlightDetail, HighlightsResponse, PageCursor, PaginatedResponse,
+    ReaderListResponse, TagSummary, TaggedItems,
+};
+use asimov_module::secrecy::{ExposeSecret, SecretString};
+use serde::de::DeserializeOwned;
+use std::{collections::BTreeMap, path::PathBuf, time::Duration};
 
-use ureq;
+/// The largest page size accepted by the Readwise list endpoints.
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+/// How the client retries rate-limited and transiently failing requests.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts per request, including the first one.
+    pub max_attempts: u32,
+    /// The longest the client will sleep before a single retry.
+    pub max_wait: Duration,
+    /// The delay before the first retry, doubled on each further attempt.
+    pub initial_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            max_wait: Duration::from_secs(120),
+            initial_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the exponential backoff delay after the given failed attempt.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_wait)
+    }
+}
+
+/// What the client does when Readwise responds with 429 Too Many Requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RateLimitPolicy {
+    /// Wait for the `Retry-After` delay, within the retry policy, and retry.
+    #[default]
+    Wait,
+    /// Fail immediately with [`ReadwiseError::RateLimited`].
+    Fail,
+}
+
+/// The default limit on the size of a response body. A single export page
+/// nests every highlight of its books, which exceeds `ureq`'s default of
+/// 10 MB for large libraries.
+pub const DEFAULT_MAX_BODY_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// The `User-Agent` sent with every request, identifying the module version.
+pub const DEFAULT_USER_AGENT: &str =
+    concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
 
 #[derive(Debug, Clone)]
 pub struct ReadwiseConfig {
     pub base_url: String,
-    pub access_token: String,
+    /// The base URL of the Reader API. When unset, it is derived from
+    /// `base_url` by replacing its `/v2` suffix with `/v3`.
+    pub reader_base_url: Option<String>,
+    /// Only exposed when building the `Authorization` header; redacted in
+    /// `Debug` output.
+    pub access_token: SecretString,
+    /// Where the access token came from, e.g. `$READWISE_API_KEY`, to name
+    /// in errors when Readwise rejects it.
+    pub token_source: Option<String>,
+    pub retry: RetryPolicy,
+    pub rate_limit: RateLimitPolicy,
+    /// The client-side request budgets per endpoint.
+    pub rate_limits: RateLimits,
+    /// Notified the first time requests to an endpoint are throttled.
+    pub on_throttle: Option<ThrottleHook>,
+    /// The page size used when a fetch call does not specify one.
+    pub page_size: Option<usize>,
+    /// How long to wait for a connection to be established.
+    pub connect_timeout: Option<Duration>,
+    /// How long to wait for the response headers, and then for the body.
+    pub read_timeout: Option<Duration>,
+    pub user_agent: String,
+    /// An HTTP(S) proxy URL. When unset, the standard `HTTPS_PROXY`,
+    /// `HTTP_PROXY` and `ALL_PROXY` environment variables are honored.
+    pub proxy: Option<String>,
+    /// A PEM file of CA certificates to trust instead of the bundled roots.
+    pub ca_bundle: Option<PathBuf>,
+    /// The largest response body to read, in bytes.
+    pub max_body_size: u64,
 }
 
 impl ReadwiseConfig {
-    pub fn new(access_token: String) -> Self {
+    pub fn new(access_token: impl Into<SecretString>) -> Self {
         Self {
             base_url: "https://readwise.io/api/v2".to_string(),
-            access_token,
+            reader_base_url: None,
+            access_token: access_token.into(),
+            token_source: None,
+            retry: RetryPolicy::default(),
+            rate_limit: RateLimitPolicy::default(),
+            rate_limits: RateLimits::default(),
+            on_throttle: None,
+            page_size: None,
+            connect_timeout: Some(Duration::from_secs(10)),
+            read_timeout: Some(Duration::from_secs(60)),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            proxy: None,
+            ca_bundle: None,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
         }
     }
 
+    pub fn builder() -> ReadwiseClientBuilder {
+        ReadwiseClientBuilder::new()
+    }
+
     pub fn endpoint_url(&self, path: &str) -> String {
         format!("{}{}", self.base_url, path)
     }
+
+    pub fn reader_endpoint_url(&self, path: &str) -> String {
+        match &self.reader_base_url {
+            Some(reader_base_url) => format!("{}{}", reader_base_url, path),
+            None => match self.base_url.strip_suffix("/v2") {
+                Some(api_url) => format!("{}/v3{}", api_url, path),
+                None => format!("{}{}", self.base_url, path),
+            },
+        }
+    }
+
+    /// Checks the settings for values the client cannot work with.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: String| Err(ReadwiseError::Config(message));
+
+        if self.access_token.expose_secret().trim().is_empty() {
+            return invalid("the Readwise access token is empty".to_string());
+        }
+        match url::Url::parse(&self.base_url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {},
+            _ => return invalid(format!("invalid base URL: {}", self.base_url)),
+        }
+        if let Some(reader_base_url) = &self.reader_base_url {
+            match url::Url::parse(reader_base_url) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => {},
+                _ => return invalid(format!("invalid Reader base URL: {}", reader_base_url)),
+            }
+        }
+        if let Some(page_size) = self.page_size
+            && !(1..=MAX_PAGE_SIZE).contains(&page_size)
+        {
+            return invalid(format!(
+                "page size must be between 1 and {}, got {}",
+                MAX_PAGE_SIZE, page_size
+            ));
+        }
+        if self.retry.max_attempts == 0 {
+            return invalid("the retry policy must allow at least one attempt".to_string());
+        }
+        if self.connect_timeout.is_some_and(|t| t.is_zero())
+            || self.read_timeout.is_some_and(|t| t.is_zero())
+        {
+            return invalid("timeouts must be greater than zero".to_string());
+        }
+        if self.max_body_size == 0 {
+            return invalid("the maximum body size must be greater than zero".to_string());
+        }
+        self.rate_limits.validate().map_err(ReadwiseError::Config)?;
+        Ok(())
+    }
 }
 
 pub struct ReadwiseClient {
     config: ReadwiseConfig,
+    transport: Box<dyn Transport>,
+    limiter: RateLimiter,
 }
 
 impl ReadwiseClient {
+    pub fn builder() -> ReadwiseClientBuilder {
+        ReadwiseClientBuilder::new()
+    }
+
+    /// Creates a client with a single pooled HTTP agent, configured from
+    /// `config`, that is reused across all requests.
     pub fn new(config: ReadwiseConfig) -> Result<Self> {
-        Ok(Self { config })
+        let transport = UreqTransport::new(&config)?;
+        Self::with_transport(config, transport)
+    }
+
+    /// Creates a client sending its requests through the given transport,
+    /// e.g. a [`FixtureTransport`](crate::api::transport::FixtureTransport)
+    /// standing in for the Readwise API.
+    pub fn with_transport(
+        config: ReadwiseConfig,
+        transport: impl Transport + 'static,
+    ) -> Result<Self> {
+        let mut limiter = RateLimiter::new(config.rate_limits.clone());
+        if let Some(hook) = &config.on_throttle {
+            limiter = limiter.with_throttle_hook(hook.clone());
+        }
+        Ok(Self {
+            limiter,
+            config,
+            transport: Box::new(transport),
+        })
     }
 
     fn auth_header(&self) -> String {
-        format!("Token {}", self.config.access_token)
+        format!("Token {}", self.config.access_token.expose_secret())
     }
 
     pub fn endpoint_url(&self, path: &str) -> String {
@@ -46,139 +230,466 @@ impl ReadwiseClient {
         path: &str,
         page_size: Option<usize>,
         page: Option<usize>,
+        filter: &[(&'static str, String)],
     ) -> String {
-        let mut url = self.endpoint_url(path);
         let mut params = vec![];
 
-        if let Some(size) = page_size {
-            params.push(format!("page_size={}", size));
+        if let Some(size) = page_size.or(self.config.page_size) {
+            params.push(("page_size", size.to_string()));
         }
         if let Some(p) = page {
-            params.push(format!("page={}", p));
+            params.push(("page", p.to_string()));
         }
+        params.extend_from_slice(filter);
 
-        if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
+        self.build_url_with_query(path, &params)
+    }
+
+    fn build_url_with_query(&self, path: &str, params: &[(&str, String)]) -> String {
+        with_query(self.endpoint_url(path), params)
+    }
+
+    fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let response = self.get(url)?;
+        serde_json::from_str(&response.body).map_err(|source| ReadwiseError::Decode {
+            endpoint: url.to_string(),
+            source,
+        })
+    }
+
+    /// Sends an authorized `GET` request, retrying per the configured
+    /// policies, and returns the first successful response.
+    fn get(&self, url: &str) -> Result<HttpResponse> {
+        let retry = &self.config.retry;
+        let mut attempt = 1;
+
+        loop {
+            self.limiter.acquire(url);
+            let request = HttpRequest::get(url).with_header("Authorization", self.auth_header());
+
+            let wait = match self.transport.send(&request) {
+                Ok(response) => {
+                    let status = response.status;
+                    match status {
+                        200..=299 => return Ok(response),
+                        401 | 403 => {
+                            return Err(ReadwiseError::Unauthorized {
+                                status,
+                                token_source: self.config.token_source.clone(),
+                            });
+                        },
+                        404 => {
+                            return Err(ReadwiseError::NotFound {
+                                url: url.to_string(),
+                            });
+                        },
+                        429 => {
+                            let retry_after = parse_retry_after(&response);
+                            let exceeds_max_wait =
+                                retry_after.is_some_and(|wait| wait > retry.max_wait);
+                            if self.config.rate_limit == RateLimitPolicy::Fail
+                                || exceeds_max_wait
+                                || attempt >= retry.max_attempts
+                            {
+                                return Err(ReadwiseError::RateLimited { retry_after });
+                            }
+                            retry_after.unwrap_or_else(|| retry.backoff(attempt))
+                        },
+                        500..=599 if attempt < retry.max_attempts => retry.backoff(attempt),
+                        _ => {
+                            return Err(ReadwiseError::Http {
+                                status,
+                                body: response.body.trim().to_string(),
+                            });
+                        },
+                    }
+                },
+                Err(e) if e.is_transient() && attempt < retry.max_attempts => {
+                    retry.backoff(attempt)
+                },
+                Err(e) => return Err(e),
+            };
+
+            #[cfg(feature = "tracing")]
+            asimov_module::tracing::warn!(
+                "request to {} failed (attempt {}/{}), retrying in {}s",
+                url,
+                attempt,
+                retry.max_attempts,
+                wait.as_secs_f32()
+            );
+
+            std::thread::sleep(wait);
+            attempt += 1;
+        }
+    }
+
+    /// Checks the access token against Readwise's `/auth/` endpoint, which
+    /// answers `204 No Content` for a valid token.
+    pub fn verify_token(&self) -> Result<AuthStatus> {
+        let response = self.get(&self.endpoint_url("/auth/"))?;
+        Ok(AuthStatus {
+            server_date: response.header("Date").map(String::from),
+        })
+    }
+
+    /// Follows the `next` links of a paginated endpoint until exhaustion,
+    /// or until `limit` results have been collected.
+    ///
+    /// The returned `next` link resumes right after the last result. It is
+    /// `None` when `limit` cut a page short, as the following page would
+    /// skip the rest of it.
+    fn fetch_all_pages<T: DeserializeOwned>(
+        &mut self,
+        path: &str,
+        page_size: Option<usize>,
+        limit: Option<usize>,
+        filter: &[(&'static str, String)],
+    ) -> Result<PaginatedResponse<T>> {
+        let page_size = match (page_size.or(self.config.page_size), limit) {
+            (Some(size), _) => size,
+            (None, Some(limit)) => limit.clamp(1, MAX_PAGE_SIZE),
+            (None, None) => MAX_PAGE_SIZE,
+        };
+
+        let mut next_url = Some(self.build_url_with_params(path, Some(page_size), None, filter));
+        let mut count = None;
+        let mut results = Vec::new();
+
+        while let Some(url) = next_url.take() {
+            let page: PaginatedResponse<T> = self.get_json(&url)?;
+            count = count.or(page.count);
+            results.extend(page.results.unwrap_or_default());
+            next_url = page.next;
+
+            if let Some(limit) = limit
+                && results.len() >= limit
+            {
+                if results.len() > limit {
+                    results.truncate(limit);
+                    next_url = None;
+                }
+                break;
+            }
         }
 
-        url
+        Ok(PaginatedResponse {
+            count,
+            next: next_url,
+            previous: None,
+            results: Some(results),
+        })
     }
 
     pub fn fetch_highlights(
         &mut self,
         page_size: Option<usize>,
         page: Option<usize>,
+        filter: &HighlightFilter,
     ) -> Result<HighlightsResponse> {
-        let url = self.build_url_with_params("/highlights/", page_size, page);
-
-        let mut response = ureq::get(&url)
-            .header("Authorization", &self.auth_header())
-            .call()
-            .map_err(|e| {
-                if e.to_string().contains("429") {
-                    anyhow::anyhow!("Rate limit exceeded (429). Please wait a minute before trying again. Consider using smaller page sizes to avoid hitting limits.")
-                } else {
-                    e.into()
-                }
-            })?;
-        let response_body: HighlightsResponse =
-            serde_json::from_str(&response.body_mut().read_to_string()?)?;
-        Ok(response_body)
+        let url =
+            self.build_url_with_params("/highlights/", page_size, page, &filter.query_params());
+        self.get_json(&url)
+    }
+
+    /// Fetches every highlight matching `filter`, transparently following
+    /// pagination.
+    pub fn fetch_all_highlights(
+        &mut self,
+        page_size: Option<usize>,
+        limit: Option<usize>,
+        filter: &HighlightFilter,
+    ) -> Result<HighlightsResponse> {
+        self.fetch_all_pages("/highlights/", page_size, limit, &filter.query_params())
+    }
+
+    pub fn fetch_highlight(&mut self, id: u64) -> Result<Highlight> {
+        let url = self.endpoint_url(&format!("/highlights/{}/", id));
+        self.get_json(&url)
+    }
+
+    /// Fetches a highlight along with the book it was made in.
+    pub fn fetch_highlight_detail(&mut self, id: u64) -> Result<HighlightDetail> {
+        let highlight = self.fetch_highlight(id)?;
+        let book = match highlight.book_id {
+            Some(book_id) => Some(self.fetch_book(book_id)?),
+            None => None,
+        };
+        Ok(HighlightDetail { highlight, book })
     }
 
     pub fn fetch_booklist(
         &mut self,
         page_size: Option<usize>,
         page: Option<usize>,
+        filter: &BookFilter,
     ) -> Result<BookListResponse> {
-        let url = self.build_url_with_params("/books/", page_size, page);
-
-        let mut response = ureq::get(&url)
-            .header("Authorization", &self.auth_header())
-            .call()
-            .map_err(|e| {
-                if e.to_string().contains("429") {
-                    anyhow::anyhow!("Rate limit exceeded (429). Please wait a minute before trying again. Consider using smaller page sizes to avoid hitting limits.")
-                } else {
-                    e.into()
-                }
-            })?;
-        let response_body: BookListResponse =
-            serde_json::from_str(&response.body_mut().read_to_string()?)?;
-        Ok(response_body)
+        let url = self.build_url_with_params("/books/", page_size, page, &filter.query_params());
+        self.get_json(&url)
+    }
+
+    /// Fetches every book matching `filter`, transparently following
+    /// pagination.
+    pub fn fetch_all_books(
+        &mut self,
+        page_size: Option<usize>,
+        limit: Option<usize>,
+        filter: &BookFilter,
+    ) -> Result<BookListResponse> {
+        self.fetch_all_pages("/books/", page_size, limit, &filter.query_params())
+    }
+
+    pub fn fetch_book(&mut self, id: u64) -> Result<Book> {
+        let url = self.endpoint_url(&format!("/books/{}/", id));
+        self.get_json(&url)
     }
 
-    pub fn fetch_highlight_tags(&mut self) -> Result<Vec<serde_json::Value>> {
-        let mut all_tags = std::collections::HashMap::new();
-        let mut page = 1;
-        let page_size = 100;
+    /// Fetches a book along with every one of its highlights, or the first
+    /// `limit` of them.
+    pub fn fetch_book_detail(
+        &mut self,
+        id: u64,
+        page_size: Option<usize>,
+        limit: Option<usize>,
+        filter: &HighlightFilter,
+    ) -> Result<BookDetail> {
+        let book = self.fetch_book(id)?;
+        let filter = HighlightFilter {
+            book_id: Some(id),
+            ..filter.clone()
+        };
+        let highlights = self.fetch_all_highlights(page_size, limit, &filter)?;
+        Ok(BookDetail {
+            book,
+            highlights: highlights.results.unwrap_or_default(),
+        })
+    }
+
+    /// Fetches today's daily review, dated with the local date.
+    pub fn fetch_daily_review(&mut self) -> Result<DailyReview> {
+        let url = self.endpoint_url("/review/");
+        let mut review: DailyReview = self.get_json(&url)?;
+        review.date = Some(chrono::Local::now().date_naive().to_string());
+        Ok(review)
+    }
+
+    /// Fetches a single page of the export endpoint, which returns books with
+    /// their highlights and tags nested.
+    pub fn fetch_export(
+        &mut self,
+        updated_after: Option<&str>,
+        page_cursor: Option<&PageCursor>,
+    ) -> Result<ExportResponse> {
+        let mut params = vec![];
+
+        if let Some(updated_after) = updated_after {
+            params.push(("updatedAfter", updated_after.to_string()));
+        }
+        if let Some(cursor) = page_cursor {
+            params.push(("pageCursor", cursor.to_string()));
+        }
+
+        let url = self.build_url_with_query("/export/", &params);
+        self.get_json(&url)
+    }
+
+    /// Fetches the whole export, following `nextPageCursor` until exhaustion,
+    /// or until `limit` books have been collected. The returned cursor is
+    /// `None` when `limit` cut a page short.
+    pub fn fetch_all_export(
+        &mut self,
+        updated_after: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<ExportResponse> {
+        let mut cursor = None;
+        let mut count = None;
+        let mut results = Vec::new();
 
         loop {
-            let highlights = self.fetch_highlights(Some(page_size), Some(page))
-            .map_err(|e| {
-                if e.to_string().contains("429") {
-                    anyhow::anyhow!("Rate limit exceeded while fetching highlights (429). Please wait a minute before trying again.")
-                } else {
-                    e
-                }
-            })?;
+            let page = self.fetch_export(updated_after, cursor.as_ref())?;
+            count = count.or(page.count);
+            results.extend(page.results.unwrap_or_default());
+            cursor = page.next_page_cursor;
 
-            if let Some(results) = highlights.results {
-                if results.is_empty() {
-                    break;
+            if let Some(limit) = limit
+                && results.len() >= limit
+            {
+                if results.len() > limit {
+                    results.truncate(limit);
+                    cursor = None;
                 }
+                break;
+            }
+            if cursor.is_none() {
+                break;
+            }
+        }
 
-                let has_next = highlights.next.is_some();
-
-                for highlight in results {
-                    if let Some(highlight_id) = highlight.id {
-                        let tags_url =
-                            self.endpoint_url(&format!("/highlights/{}/tags", highlight_id));
-
-                        let mut response = ureq::get(&tags_url)
-                            .header("Authorization", &self.auth_header())
-                            .call()
-                            .map_err(|e| {
-                                if e.to_string().contains("429") {
-                                    anyhow::anyhow!("Rate limit exceeded while fetching tags (429). Please wait a minute before trying again.")
-                                } else {
-                                    e.into()
-                                }
-                            })?;
-
-                        let response_body = response.body_mut().read_to_string()?;
-
-                        let tags_data: serde_json::Value = serde_json::from_str(&response_body)?;
-
-                        if let Some(tag_results) = tags_data["results"].as_array() {
-                            for tag in tag_results {
-                                if let (Some(name), Some(id)) =
-                                    (tag["name"].as_str(), tag["id"].as_u64())
-                                {
-                                    all_tags.insert(
-                                        name.to_string(),
-                                        serde_json::json!({
-                                            "name": name,
-                                            "id": id
-                                        }),
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
+        Ok(ExportResponse {
+            count,
+            next_page_cursor: cursor,
+            results: Some(results),
+        })
+    }
 
-                if has_next {
-                    page += 1;
-                } else {
-                    break;
+    /// Collects every book and highlight tag from the export endpoint,
+    /// counting how many highlights and books carry each tag.
+    ///
+    /// Tags are embedded in the export, so this completes in one request
+    /// per page of books rather than one request per highlight.
+    pub fn fetch_tags(&mut self) -> Result<Vec<TagSummary>> {
+        let export = self.fetch_all_export(None, None)?;
+        Ok(summarize_tags(
+            export.results.as_deref().unwrap_or_default(),
+        ))
+    }
+
+    /// Fetches a single page of Reader documents matching `filter`.
+    pub fn fetch_documents(
+        &mut self,
+        filter: &DocumentFilter,
+        page_cursor: Option<&PageCursor>,
+    ) -> Result<ReaderListResponse> {
+        let mut params = filter.query_params();
+        if let Some(cursor) = page_cursor {
+            params.push(("pageCursor", cursor.to_string()));
+        }
+
+        let url = with_query(self.config.reader_endpoint_url("/list/"), &params);
+        self.get_json(&url)
+    }
+
+    /// Fetches every Reader document matching `filter`, following
+    /// `nextPageCursor` until exhaustion, or until `limit` documents have
+    /// been collected. The returned cursor is `None` when `limit` cut a page
+    /// short.
+    pub fn fetch_all_documents(
+        &mut self,
+        filter: &DocumentFilter,
+        limit: Option<usize>,
+    ) -> Result<ReaderListResponse> {
+        let mut cursor = None;
+        let mut count = None;
+        let mut results = Vec::new();
+
+        loop {
+            let page = self.fetch_documents(filter, cursor.as_ref())?;
+            count = count.or(page.count);
+            results.extend(page.results.unwrap_or_default());
+            cursor = page.next_page_cursor;
+
+            if let Some(limit) = limit
+                && results.len() >= limit
+            {
+                if results.len() > limit {
+                    results.truncate(limit);
+                    cursor = None;
                 }
-            } else {
                 break;
             }
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        Ok(ReaderListResponse {
+            count,
+            next_page_cursor: cursor,
+            results: Some(results),
+        })
+    }
+
+    /// Collects every highlight and book tagged `name` from the export
+    /// endpoint, optionally only those updated after a point in time.
+    pub fn fetch_tagged(&mut self, name: &str, updated_after: Option<&str>) -> Result<TaggedItems> {
+        let export = self.fetch_all_export(updated_after, None)?;
+        Ok(collect_tagged(
+            export.results.as_deref().unwrap_or_default(),
+            name,
+        ))
+    }
+}
+
+/// Appends `params` to `url` as its query string.
+fn with_query(mut url: String, params: &[(&str, String)]) -> String {
+    if !params.is_empty() {
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(params)
+            .finish();
+        url.push('?');
+        url.push_str(&query);
+    }
+    url
+}
+
+/// Parses a `Retry-After` header given either in seconds or as an HTTP date.
+fn parse_retry_after(response: &HttpResponse) -> Option<Duration> {
+    let value = response.header("Retry-After")?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.signed_duration_since(chrono::Utc::now());
+    Some(wait.to_std().unwrap_or_default())
+}
+
+/// Aggregates the book-level and highlight-level tags of exported books,
+/// ordered by tag name.
+pub fn summarize_tags(books: &[ExportBook]) -> Vec<TagSummary> {
+    fn entry<'a>(
+        tags: &'a mut BTreeMap<String, TagSummary>,
+        tag: &ExportTag,
+    ) -> Option<&'a mut TagSummary> {
+        let name = tag.name.as_ref()?;
+        Some(tags.entry(name.clone()).or_insert_with(|| TagSummary {
+            id: tag.id,
+            name: name.clone(),
+            highlight_count: 0,
+            book_count: 0,
+        }))
+    }
+
+    let mut tags = BTreeMap::new();
+
+    for book in books {
+        for tag in book.book_tags.iter().flatten() {
+            if let Some(summary) = entry(&mut tags, tag) {
+                summary.book_count += 1;
+            }
+        }
+        for highlight in book.highlights.iter().flatten() {
+            for tag in highlight.tags.iter().flatten() {
+                if let Some(summary) = entry(&mut tags, tag) {
+                    summary.highlight_count += 1;
+                }
+            }
         }
+    }
+
+    tags.into_values().collect()
+}
 
-        Ok(all_tags.values().cloned().collect())
+/// Picks the highlights and books carrying the tag `name` out of an export.
+pub fn collect_tagged(books: &[ExportBook], name: &str) -> TaggedItems {
+    let has_tag = |tags: &Option<Vec<ExportTag>>| {
+        tags.iter()
+            .flatten()
+            .any(|tag| tag.name.as_deref() == Some(name))
+    };
+
+    let mut tagged = TaggedItems {
+        name: name.to_string(),
+        ..Default::default()
+    };
+    for book in books {
+        if has_tag(&book.book_tags) {
+            tagged.books.push(book.into());
+        }
+        for highlight in book.highlights.iter().flatten() {
+            if has_tag(&highlight.tags) {
+                tagged.highlights.push(highlight.into());
+            }
+        }
     }
+    tagged
 }
@@ -0,0 +1,145 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::api::error::{ReadwiseError, Result};
+use crate::config::ConfigFile;
+use asimov_module::secrecy::{ExposeSecret, SecretString};
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+/// Where the client obtains its Readwise access token.
+#[derive(Clone, Debug)]
+pub enum TokenSource {
+    /// The token itself.
+    Value(SecretString),
+    /// The name of an environment variable holding the token.
+    Env(String),
+    /// The name of an environment variable holding the path of a file that
+    /// contains the token, e.g. a mounted secret.
+    EnvFile(String),
+    /// A file containing the token.
+    File(PathBuf),
+    /// A shell command printing the token on stdout, e.g. a password manager.
+    Command(String),
+    /// A profile in the module's config file, which in turn names a token,
+    /// token file or token command.
+    Profile { path: PathBuf, name: String },
+}
+
+impl TokenSource {
+    /// Returns the token, failing if the source does not provide one.
+    pub fn resolve(&self) -> Result<SecretString> {
+        self.lookup()?
+            .ok_or_else(|| ReadwiseError::Config(format!("no token found in {}", self)))
+    }
+
+    /// Returns the token, or `None` if this source is not configured.
+    ///
+    /// A source that is configured but broken, such as an unreadable file or
+    /// a failing command, is an error rather than `None`.
+    pub fn lookup(&self) -> Result<Option<SecretString>> {
+        let token = match self {
+            TokenSource::Value(token) => Some(token.expose_secret().to_string()),
+            TokenSource::Env(name) => asimov_module::getenv::var(name),
+            TokenSource::EnvFile(name) => match asimov_module::getenv::var(name) {
+                Some(path) => Some(read_token_file(&PathBuf::from(path))?),
+                None => None,
+            },
+            TokenSource::File(path) => Some(read_token_file(path)?),
+            TokenSource::Command(command) => Some(run_token_command(command)?),
+            TokenSource::Profile { path, name } => {
+                let config = ConfigFile::load(path)
+                    .map_err(|e| ReadwiseError::Config(format!("{:#}", e)))?;
+                let Some(profile) = config.profile(name) else {
+                    return Ok(None);
+                };
+                for source in profile.token_sources() {
+                    if let Some(token) = source.lookup()? {
+                        return Ok(Some(token));
+                    }
+                }
+                return Ok(None);
+            },
+        };
+
+        Ok(token
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty())
+            .map(SecretString::from))
+    }
+}
+
+impl std::fmt::Display for TokenSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenSource::Value(_) => write!(f, "the given token"),
+            TokenSource::Env(name) => write!(f, "${}", name),
+            TokenSource::EnvFile(name) => write!(f, "the file named by ${}", name),
+            TokenSource::File(path) => write!(f, "{}", path.display()),
+            TokenSource::Command(command) => write!(f, "`{}`", command),
+            TokenSource::Profile { path, name } => {
+                write!(f, "profile \"{}\" in {}", name, path.display())
+            },
+        }
+    }
+}
+
+/// Returns the token from the first source providing one, in order of
+/// precedence, or an error listing every source that was tried.
+pub fn resolve_first(sources: &[TokenSource]) -> Result<SecretString> {
+    find_first(sources).map(|(token, _)| token)
+}
+
+/// Like [`resolve_first`], but also returns the source the token came from.
+pub fn find_first(sources: &[TokenSource]) -> Result<(SecretString, &TokenSource)> {
+    for source in sources {
+        if let Some(token) = source.lookup()? {
+            return Ok((token, source));
+        }
+    }
+    let tried: Vec<_> = sources.iter().map(ToString::to_string).collect();
+    Err(ReadwiseError::Config(format!(
+        "no Readwise access token found (tried {})",
+        tried.join(", ")
+    )))
+}
+
+fn read_token_file(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|e| {
+        ReadwiseError::Config(format!(
+            "failed to read token file {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+fn run_token_command(command: &str) -> Result<String> {
+    let failed = |reason: String| {
+        ReadwiseError::Config(format!("token command `{}` failed: {}", command, reason))
+    };
+
+    #[cfg(windows)]
+    let mut process = {
+        let mut process = Command::new("cmd");
+        process.args(["/C", command]);
+        process
+    };
+    #[cfg(not(windows))]
+    let mut process = {
+        let mut process = Command::new("sh");
+        process.args(["-c", command]);
+        process
+    };
+
+    let output = process
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| failed(e.to_string()))?;
+    if !output.status.success() {
+        return Err(failed(output.status.to_string()));
+    }
+    String::from_utf8(output.stdout).map_err(|e| failed(e.to_string()))
+}
@@ -0,0 +1,287 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::api::error::{ReadwiseError, Result};
+use crate::api::readwise::ReadwiseConfig;
+use crate::api::recording::redact;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    path::Path,
+    sync::Mutex,
+};
+use ureq::tls::{Certificate, PemItem, RootCerts};
+
+/// An outgoing HTTP `GET` request.
+///
+/// The `Authorization` header is redacted in `Debug` output.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpRequest {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl std::fmt::Debug for HttpRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpRequest")
+            .field("url", &self.url)
+            .field("headers", &redact(self).headers)
+            .finish()
+    }
+}
+
+impl HttpRequest {
+    pub fn get(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            headers: Vec::new(),
+        }
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// An HTTP response with its body fully read.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Returns the first header with the given name, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A failure of a [`Transport`] to get a response at all, such as a refused
+/// connection or a timeout.
+///
+/// Transient failures are retried by the client, permanent ones are not.
+#[derive(Debug)]
+pub struct TransportError {
+    error: Box<dyn std::error::Error + Send + Sync>,
+    transient: bool,
+}
+
+impl TransportError {
+    /// A failure that may not recur, such as a dropped connection.
+    pub fn transient(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self {
+            error: error.into(),
+            transient: true,
+        }
+    }
+
+    /// A failure that retrying cannot fix, such as an untrusted certificate.
+    pub fn permanent(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self {
+            error: error.into(),
+            transient: false,
+        }
+    }
+
+    pub fn is_transient(&self) -> bool {
+        self.transient
+    }
+
+    /// Returns the underlying error, e.g. a `ureq::Error`.
+    pub fn get_ref(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        self.error.as_ref()
+    }
+}
+
+impl std::fmt::Display for TransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+impl From<ureq::Error> for TransportError {
+    fn from(error: ureq::Error) -> Self {
+        match error {
+            ureq::Error::Io(_)
+            | ureq::Error::Timeout(_)
+            | ureq::Error::ConnectionFailed
+            | ureq::Error::HostNotFound => Self::transient(error),
+            _ => Self::permanent(error),
+        }
+    }
+}
+
+/// The HTTP layer used by [`ReadwiseClient`](crate::api::readwise::ReadwiseClient).
+///
+/// Implementations return every HTTP status as a response; only failures to
+/// get a response at all are errors, usually a [`TransportError`].
+pub trait Transport: Send + Sync {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse>;
+}
+
+impl<T: Transport + ?Sized> Transport for std::sync::Arc<T> {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        (**self).send(request)
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        (**self).send(request)
+    }
+}
+
+/// The default transport, backed by `ureq`.
+#[derive(Debug)]
+pub struct UreqTransport {
+    agent: ureq::Agent,
+    max_body_size: u64,
+}
+
+impl UreqTransport {
+    /// Creates the agent from the timeouts, user agent, proxy, CA bundle and
+    /// body size limit in `config`.
+    pub fn new(config: &ReadwiseConfig) -> Result<Self> {
+        let mut builder = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .user_agent(config.user_agent.as_str())
+            .timeout_connect(config.connect_timeout)
+            .timeout_recv_response(config.read_timeout)
+            .timeout_recv_body(config.read_timeout);
+
+        if let Some(proxy) = &config.proxy {
+            let proxy = ureq::Proxy::new(proxy)
+                .map_err(|e| ReadwiseError::Config(format!("invalid proxy {}: {}", proxy, e)))?;
+            builder = builder.proxy(Some(proxy));
+        }
+
+        if let Some(path) = &config.ca_bundle {
+            let certs = load_ca_bundle(path).map_err(|e| {
+                ReadwiseError::Config(format!("invalid CA bundle {}: {}", path.display(), e))
+            })?;
+            let tls_config = ureq::tls::TlsConfig::builder()
+                .root_certs(RootCerts::new_with_certs(&certs))
+                .build();
+            builder = builder.tls_config(tls_config);
+        }
+
+        Ok(Self {
+            agent: ureq::Agent::new_with_config(builder.build()),
+            max_body_size: config.max_body_size,
+        })
+    }
+}
+
+fn load_ca_bundle(path: &Path) -> std::result::Result<Vec<Certificate<'static>>, String> {
+    let pem = std::fs::read(path).map_err(|e| e.to_string())?;
+    let mut certs = Vec::new();
+    for item in ureq::tls::parse_pem(&pem) {
+        if let PemItem::Certificate(cert) = item.map_err(|e| e.to_string())? {
+            certs.push(cert);
+        }
+    }
+    if certs.is_empty() {
+        return Err("no certificates found".to_string());
+    }
+    Ok(certs)
+}
+
+impl Transport for UreqTransport {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        let mut builder = self.agent.get(&request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        let mut response = builder.call().map_err(TransportError::from)?;
+
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = value.to_str().ok()?;
+                Some((name.as_str().to_string(), value.to_string()))
+            })
+            .collect();
+
+        Ok(HttpResponse {
+            status: response.status().as_u16(),
+            headers,
+            body: response
+                .body_mut()
+                .with_config()
+                .limit(self.max_body_size)
+                .read_to_string()
+                .map_err(TransportError::from)?,
+        })
+    }
+}
+
+/// An in-memory transport serving canned responses by URL, for tests and
+/// local stand-ins of the Readwise API.
+///
+/// Responses queued for the same URL are served in order, and the last one
+/// is repeated once the queue is drained. Unknown URLs get a 404.
+#[derive(Debug, Default)]
+pub struct FixtureTransport {
+    responses: Mutex<HashMap<String, VecDeque<HttpResponse>>>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl FixtureTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_response(self, url: impl Into<String>, response: HttpResponse) -> Self {
+        self.push_response(url, response);
+        self
+    }
+
+    pub fn push_response(&self, url: impl Into<String>, response: HttpResponse) {
+        self.responses
+            .lock()
+            .unwrap()
+            .entry(url.into())
+            .or_default()
+            .push_back(response);
+    }
+
+    /// Returns every request sent through this transport so far.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Transport for FixtureTransport {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        self.requests.lock().unwrap().push(request.clone());
+
+        let mut responses = self.responses.lock().unwrap();
+        let response = match responses.get_mut(&request.url) {
+            Some(queue) if queue.len() > 1 => queue.pop_front(),
+            Some(queue) => queue.front().cloned(),
+            None => None,
+        };
+        Ok(response.unwrap_or_else(|| HttpResponse::new(404, r#"{"detail":"Not found."}"#)))
+    }
+}
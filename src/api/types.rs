@@ -25,7 +25,7 @@ pub struct HighlightRequest {
     pub highlight_url: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Highlight {
     pub id: Option<u64>,
     pub title: Option<String>,
@@ -46,11 +46,20 @@ pub struct Highlight {
     pub location_type: Option<String>,
     pub highlighted_at: Option<String>,
     pub highlight_url: Option<String>,
+    pub book_id: Option<u64>,
+}
+
+/// A single highlight with the book it was made in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighlightDetail {
+    #[serde(flatten)]
+    pub highlight: Highlight,
+    pub book: Option<Book>,
 }
 
 pub type HighlightsResponse = PaginatedResponse<Highlight>;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Book {
     pub id: Option<u64>,
     pub title: Option<String>,
@@ -70,6 +79,14 @@ pub struct Book {
 
 pub type BookListResponse = PaginatedResponse<Book>;
 
+/// A single book with all of its highlights.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookDetail {
+    #[serde(flatten)]
+    pub book: Book,
+    pub highlights: Vec<Highlight>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tag {
     pub id: Option<u64>,
@@ -89,6 +106,211 @@ pub struct SimpleTag {
 
 pub type SimpleTagsResponse = Vec<SimpleTag>;
 
+/// An opaque cursor for cursor-paginated endpoints, which Readwise returns
+/// either as a number or as a string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PageCursor {
+    Number(u64),
+    String(String),
+}
+
+impl std::fmt::Display for PageCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PageCursor::Number(n) => write!(f, "{}", n),
+            PageCursor::String(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportTag {
+    pub id: Option<u64>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportHighlight {
+    pub id: Option<u64>,
+    pub text: Option<String>,
+    pub note: Option<String>,
+    pub location: Option<i32>,
+    pub location_type: Option<String>,
+    pub color: Option<String>,
+    pub highlighted_at: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub external_id: Option<String>,
+    pub end_location: Option<i32>,
+    pub url: Option<String>,
+    pub book_id: Option<u64>,
+    pub tags: Option<Vec<ExportTag>>,
+    pub is_favorite: Option<bool>,
+    pub is_discard: Option<bool>,
+    pub readwise_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportBook {
+    pub user_book_id: Option<u64>,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub readable_title: Option<String>,
+    pub source: Option<String>,
+    pub cover_image_url: Option<String>,
+    pub unique_url: Option<String>,
+    pub book_tags: Option<Vec<ExportTag>>,
+    pub category: Option<String>,
+    pub document_note: Option<String>,
+    pub summary: Option<String>,
+    pub readwise_url: Option<String>,
+    pub source_url: Option<String>,
+    pub asin: Option<String>,
+    pub highlights: Option<Vec<ExportHighlight>>,
+}
+
+impl From<&ExportHighlight> for Highlight {
+    fn from(highlight: &ExportHighlight) -> Self {
+        Self {
+            id: highlight.id,
+            text: highlight.text.clone(),
+            note: highlight.note.clone(),
+            location: highlight.location,
+            location_type: highlight.location_type.clone(),
+            highlighted_at: highlight.highlighted_at.clone(),
+            updated: highlight.updated_at.clone(),
+            highlight_url: highlight.url.clone(),
+            book_id: highlight.book_id,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&ExportBook> for Book {
+    fn from(book: &ExportBook) -> Self {
+        Self {
+            id: book.user_book_id,
+            title: book.title.clone(),
+            author: book.author.clone(),
+            category: book.category.clone(),
+            source: book.source.clone(),
+            num_highlights: book.highlights.as_ref().map(|h| h.len() as u32),
+            cover_image_url: book.cover_image_url.clone(),
+            source_url: book.source_url.clone(),
+            asin: book.asin.clone(),
+            tags: book
+                .book_tags
+                .as_ref()
+                .map(|tags| tags.iter().filter_map(|tag| tag.name.clone()).collect()),
+            document_note: book.document_note.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The highlights and books carrying one tag.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaggedItems {
+    pub name: String,
+    pub highlights: Vec<Highlight>,
+    pub books: Vec<Book>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewHighlight {
+    pub id: Option<u64>,
+    pub text: Option<String>,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub url: Option<String>,
+    pub source_url: Option<String>,
+    pub source_type: Option<String>,
+    pub category: Option<String>,
+    pub location_type: Option<String>,
+    pub location: Option<i32>,
+    pub note: Option<String>,
+    pub highlighted_at: Option<String>,
+    pub highlight_url: Option<String>,
+    pub image_url: Option<String>,
+    pub api_source: Option<String>,
+}
+
+/// The highlights up for today's daily review.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyReview {
+    pub review_id: Option<u64>,
+    pub review_url: Option<String>,
+    pub review_completed: Option<bool>,
+    pub highlights: Option<Vec<ReviewHighlight>>,
+    /// The local date the review was fetched on, as Readwise sends none.
+    #[serde(default)]
+    pub date: Option<String>,
+}
+
+/// A document saved to Reader.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReaderDocument {
+    pub id: Option<String>,
+    pub url: Option<String>,
+    pub source_url: Option<String>,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub source: Option<String>,
+    pub category: Option<String>,
+    pub location: Option<String>,
+    /// Keyed by tag slug, each with the tag's `name`.
+    pub tags: Option<serde_json::Value>,
+    pub site_name: Option<String>,
+    pub word_count: Option<u64>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    /// A date or a Unix timestamp in milliseconds, depending on the source.
+    pub published_date: Option<serde_json::Value>,
+    pub summary: Option<String>,
+    pub image_url: Option<String>,
+    pub parent_id: Option<String>,
+    /// How far the document has been read, from 0 to 1.
+    pub reading_progress: Option<f64>,
+    pub first_opened_at: Option<String>,
+    pub last_opened_at: Option<String>,
+    pub saved_at: Option<String>,
+    pub last_moved_at: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReaderListResponse {
+    pub count: Option<u32>,
+    #[serde(rename = "nextPageCursor")]
+    pub next_page_cursor: Option<PageCursor>,
+    pub results: Option<Vec<ReaderDocument>>,
+}
+
+/// A tag with the number of highlights and books carrying it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagSummary {
+    pub id: Option<u64>,
+    pub name: String,
+    pub highlight_count: u32,
+    pub book_count: u32,
+}
+
+/// The outcome of a successful token check against `/auth/`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthStatus {
+    /// The server's `Date` header, if it sent one.
+    pub server_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportResponse {
+    pub count: Option<u32>,
+    #[serde(rename = "nextPageCursor")]
+    pub next_page_cursor: Option<PageCursor>,
+    pub results: Option<Vec<ExportBook>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ApiResponse {
@@ -96,6 +318,7 @@ pub enum ApiResponse {
     BookList(BookListResponse),
     Tags(TagsResponse),
     SimpleTags(SimpleTagsResponse),
+    Export(ExportResponse),
 }
 
 pub use Book as BookResponse;
@@ -106,18 +329,60 @@ pub enum ReadwiseType {
     Highlights,
     Booklist,
     Tags,
+    Export,
+    Book,
+    Highlight,
+    Tag,
+    Articles,
+    Tweets,
+    Podcasts,
+    Supplementals,
+    DailyReview,
+    Reader,
+    ReaderNew,
+    ReaderLater,
+    ReaderArchive,
+    ReaderFeed,
 }
 
 impl ReadwiseType {
     pub const HIGHLIGHTS_ID: &'static str = "readwise-highlights";
     pub const BOOKLIST_ID: &'static str = "readwise-booklist";
     pub const TAGS_ID: &'static str = "readwise-tags";
+    pub const EXPORT_ID: &'static str = "readwise-export";
+    pub const BOOK_ID: &'static str = "readwise-book";
+    pub const HIGHLIGHT_ID: &'static str = "readwise-highlight";
+    pub const TAG_ID: &'static str = "readwise-tag";
+    pub const ARTICLES_ID: &'static str = "readwise-articles";
+    pub const TWEETS_ID: &'static str = "readwise-tweets";
+    pub const PODCASTS_ID: &'static str = "readwise-podcasts";
+    pub const SUPPLEMENTALS_ID: &'static str = "readwise-supplementals";
+    pub const DAILY_REVIEW_ID: &'static str = "readwise-daily-review";
+    pub const READER_ID: &'static str = "readwise-reader";
+    pub const READER_NEW_ID: &'static str = "readwise-reader-new";
+    pub const READER_LATER_ID: &'static str = "readwise-reader-later";
+    pub const READER_ARCHIVE_ID: &'static str = "readwise-reader-archive";
+    pub const READER_FEED_ID: &'static str = "readwise-reader-feed";
 
     pub fn as_str(&self) -> &'static str {
         match self {
             ReadwiseType::Highlights => Self::HIGHLIGHTS_ID,
             ReadwiseType::Booklist => Self::BOOKLIST_ID,
             ReadwiseType::Tags => Self::TAGS_ID,
+            ReadwiseType::Export => Self::EXPORT_ID,
+            ReadwiseType::Book => Self::BOOK_ID,
+            ReadwiseType::Highlight => Self::HIGHLIGHT_ID,
+            ReadwiseType::Tag => Self::TAG_ID,
+            ReadwiseType::Articles => Self::ARTICLES_ID,
+            ReadwiseType::Tweets => Self::TWEETS_ID,
+            ReadwiseType::Podcasts => Self::PODCASTS_ID,
+            ReadwiseType::Supplementals => Self::SUPPLEMENTALS_ID,
+            ReadwiseType::DailyReview => Self::DAILY_REVIEW_ID,
+            ReadwiseType::Reader => Self::READER_ID,
+            ReadwiseType::ReaderNew => Self::READER_NEW_ID,
+            ReadwiseType::ReaderLater => Self::READER_LATER_ID,
+            ReadwiseType::ReaderArchive => Self::READER_ARCHIVE_ID,
+            ReadwiseType::ReaderFeed => Self::READER_FEED_ID,
         }
     }
 }
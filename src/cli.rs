@@ -0,0 +1,536 @@
+// This is free and unencumbered software released into the public domain.
+
+//! The command line shared by the fetcher and the importer, which differ only
+//! in how they output what they fetched.
+
+use crate::api::filter::{BookCategory, BookFilter, DocumentFilter, HighlightFilter};
+use crate::api::limiter::{Budget, RateLimits, parse_rate_limit};
+use crate::api::readwise::ReadwiseClient;
+use crate::api::recording::{REDACTED, RecordingTransport, ReplayTransport};
+use crate::api::token::{self, TokenSource};
+use crate::api::transport::UreqTransport;
+use crate::api::types::{
+    BookDetail, BookListResponse, DailyReview, ExportResponse, HighlightDetail, HighlightsResponse,
+    ReaderListResponse, ReadwiseType, TagSummary, TaggedItems,
+};
+use crate::config::{ConfigFile, DEFAULT_PROFILE, PROFILE_VAR};
+use crate::output::{
+    CliError, ErrorFormat, OutputFormat, write_error, write_json_output, write_jsonl_from_results,
+};
+use crate::providers::readwise::{category_of, location_of};
+use crate::sync::{self, SyncState, parse_since};
+use crate::{doctor, match_provider_for};
+use asimov_module::getenv;
+use asimov_module::secrecy::SecretString;
+use clientele::{StandardOptions, SysexitsError};
+use serde::Serialize;
+use std::{error::Error, path::PathBuf, time::Duration};
+
+/// The options shared by the fetcher and the importer.
+#[derive(clap::Args)]
+pub struct CommonOptions {
+    #[arg(value_name = "INPUT-URL")]
+    pub input_url: Option<String>,
+
+    #[arg(long, value_name = "SIZE")]
+    pub page_size: Option<usize>,
+
+    #[arg(long, value_name = "NUM")]
+    pub page: Option<usize>,
+
+    #[arg(long, value_name = "COUNT")]
+    pub limit: Option<usize>,
+
+    #[arg(long, value_name = "RFC3339")]
+    pub since: Option<String>,
+
+    #[arg(long, value_name = "PATH")]
+    pub state: Option<PathBuf>,
+
+    #[arg(long, value_name = "RFC3339", value_parser = parse_since)]
+    pub updated_before: Option<String>,
+
+    #[arg(long, value_name = "ID")]
+    pub book_id: Option<u64>,
+
+    #[arg(long, value_name = "RFC3339", value_parser = parse_since)]
+    pub highlighted_after: Option<String>,
+
+    #[arg(long, value_name = "RFC3339", value_parser = parse_since)]
+    pub highlighted_before: Option<String>,
+
+    #[arg(long, value_name = "CATEGORY")]
+    pub category: Option<BookCategory>,
+
+    #[arg(long, value_name = "SOURCE")]
+    pub source: Option<String>,
+
+    #[arg(long, value_name = "RFC3339", value_parser = parse_since)]
+    pub last_highlight_after: Option<String>,
+
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    #[arg(long, visible_alias = "doctor")]
+    pub check_auth: bool,
+
+    #[arg(long, value_name = "COMMAND")]
+    pub token_command: Option<String>,
+
+    #[arg(long, value_name = "URL")]
+    pub base_url: Option<String>,
+
+    #[arg(long, value_name = "SECONDS")]
+    pub connect_timeout: Option<u64>,
+
+    #[arg(long, value_name = "SECONDS")]
+    pub read_timeout: Option<u64>,
+
+    #[arg(long, value_name = "URL")]
+    pub proxy: Option<String>,
+
+    #[arg(long, value_name = "PATH")]
+    pub ca_bundle: Option<PathBuf>,
+
+    #[arg(long, value_name = "DIR", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    #[arg(long, value_name = "DIR")]
+    pub replay: Option<PathBuf>,
+
+    #[arg(long, value_name = "NUM")]
+    pub max_attempts: Option<u32>,
+
+    #[arg(long, value_name = "SECONDS")]
+    pub max_retry_wait: Option<u64>,
+
+    #[arg(long, value_name = "ENDPOINT=REQUESTS", value_parser = parse_rate_limit)]
+    pub rate_limit: Vec<(String, Budget)>,
+
+    #[arg(long, conflicts_with = "rate_limit")]
+    pub no_rate_limit: bool,
+
+    #[arg(value_name = "FORMAT", short = 'o', long)]
+    pub output: Option<OutputFormat>,
+
+    #[arg(long, value_name = "FORMAT")]
+    pub error_format: Option<ErrorFormat>,
+
+    #[clap(flatten)]
+    pub flags: StandardOptions,
+}
+
+/// What was fetched for an input URL, as returned by the Readwise APIs.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum Fetched {
+    Highlights(HighlightsResponse),
+    Books(BookListResponse),
+    Tags(Vec<TagSummary>),
+    Export(ExportResponse),
+    Book(Box<BookDetail>),
+    Highlight(Box<HighlightDetail>),
+    Tagged(TaggedItems),
+    DailyReview(DailyReview),
+    Documents(ReaderListResponse),
+}
+
+/// How fetched resources are to be written out.
+#[derive(Debug)]
+pub struct Output {
+    pub format: OutputFormat,
+    /// The provider ID of the list kind, e.g. the book list for `/books/articles`.
+    pub list_id: &'static str,
+    /// The account of the selected profile, if it names one.
+    pub account: Option<String>,
+}
+
+/// Runs a command line program with the given options, handing whatever it
+/// fetched to `write_output`, and returns the exit code. Errors are reported
+/// on stderr in the requested `--error-format`.
+pub fn main<F>(options: CommonOptions, write_output: F) -> SysexitsError
+where
+    F: FnOnce(Fetched, &Output) -> Result<(), Box<dyn Error>>,
+{
+    let error_format = options.error_format.clone().unwrap_or_default();
+
+    match run(options, write_output) {
+        Ok(exit_code) => exit_code,
+        Err(error) => write_error(error.as_ref(), &error_format),
+    }
+}
+
+fn run<F>(options: CommonOptions, write_output: F) -> Result<SysexitsError, Box<dyn Error>>
+where
+    F: FnOnce(Fetched, &Output) -> Result<(), Box<dyn Error>>,
+{
+    use SysexitsError::*;
+
+    if options.flags.version {
+        println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+        return Ok(EX_OK);
+    }
+
+    if options.flags.license {
+        print!("{}", include_str!("../UNLICENSE"));
+        return Ok(EX_OK);
+    }
+
+    #[cfg(feature = "tracing")]
+    asimov_module::init_tracing_subscriber(&options.flags).expect("failed to initialize logging");
+
+    let config_path = ConfigFile::default_path();
+    let config_file = match &config_path {
+        Some(path) => match ConfigFile::load(path) {
+            Ok(config_file) => config_file,
+            Err(err) => {
+                return Err(CliError::config(format!("{:#}", err)).into());
+            },
+        },
+        None => ConfigFile::default(),
+    };
+
+    let selected_profile = options.profile.or_else(|| getenv::var(PROFILE_VAR));
+    let profile_name = selected_profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    let profile = match &selected_profile {
+        Some(name) => match config_file.require_profile(name) {
+            Ok(profile) => Some(profile),
+            Err(err) => {
+                return Err(CliError::config(format!("{:#}", err)).into());
+            },
+        },
+        None => config_file.profile(DEFAULT_PROFILE),
+    };
+    let account = config_file.account(profile_name);
+
+    // Token sources in order of precedence. A selected profile's own token
+    // wins over the environment, which may belong to another account.
+    let mut token_sources = vec![];
+    if let Some(command) = options.token_command {
+        token_sources.push(TokenSource::Command(command));
+    }
+    if selected_profile.is_none() {
+        token_sources.push(TokenSource::Env("READWISE_API_KEY".to_string()));
+        token_sources.push(TokenSource::EnvFile("READWISE_API_KEY_FILE".to_string()));
+    }
+    if let Some(path) = config_path {
+        token_sources.push(TokenSource::Profile {
+            path,
+            name: profile_name.to_string(),
+        });
+    }
+
+    let mut builder = ReadwiseClient::builder();
+    if let Some(profile) = profile {
+        builder = builder.profile(profile);
+    }
+    if let Some(base_url) = options
+        .base_url
+        .or_else(|| getenv::var("READWISE_BASE_URL"))
+    {
+        builder = builder.base_url(base_url);
+    }
+    if let Some(connect_timeout) = options.connect_timeout {
+        builder = builder.connect_timeout(Duration::from_secs(connect_timeout));
+    }
+    if let Some(read_timeout) = options.read_timeout {
+        builder = builder.read_timeout(Duration::from_secs(read_timeout));
+    }
+    if let Some(proxy) = options.proxy {
+        builder = builder.proxy(proxy);
+    }
+    if let Some(ca_bundle) = options.ca_bundle {
+        builder = builder.ca_bundle(ca_bundle);
+    }
+    if let Some(max_attempts) = options.max_attempts {
+        builder = builder.max_attempts(max_attempts);
+    }
+    if let Some(max_retry_wait) = options.max_retry_wait {
+        builder = builder.max_retry_wait(Duration::from_secs(max_retry_wait));
+    }
+    if options.no_rate_limit {
+        builder = builder.rate_limits(RateLimits::unlimited());
+    }
+    for (endpoint, budget) in &options.rate_limit {
+        builder = builder.rate_limit(endpoint, *budget);
+    }
+    // Shown regardless of the log level, as a long import would otherwise
+    // seem to hang.
+    builder = builder.on_throttle(|endpoint, budget| {
+        eprintln!(
+            "Throttling requests to {} to {} to stay within Readwise's rate limits",
+            endpoint, budget
+        );
+    });
+
+    if options.check_auth {
+        let report = doctor::run(builder, &token_sources);
+        match options.output {
+            Some(OutputFormat::Json) => write_json_output(&report)?,
+            Some(OutputFormat::Jsonl) => write_jsonl_from_results(Some(&report.checks))?,
+            None => print!("{}", report),
+        }
+        return Ok(report.exit_code());
+    }
+
+    let Some(input_url) = options.input_url else {
+        return Err(
+            CliError::usage("Missing INPUT-URL argument. Use --help for usage info").into(),
+        );
+    };
+
+    // Replaying recorded responses needs no credentials.
+    builder = match token::find_first(&token_sources) {
+        Ok((api_key, source)) => builder.resolved_token(api_key, source),
+        Err(_) if options.replay.is_some() => builder.access_token(SecretString::from(REDACTED)),
+        Err(err) => {
+            return Err(
+                CliError::config(format!("{}. Run `asimov module config readwise`", err)).into(),
+            );
+        },
+    };
+
+    let mut api = match (options.record, options.replay) {
+        // Recorded responses are replayed as fast as they can be read.
+        (_, Some(dir)) => builder
+            .transport(ReplayTransport::new(dir))
+            .rate_limits(RateLimits::unlimited())
+            .build()?,
+        (Some(dir), None) => {
+            let transport = UreqTransport::new(&builder.build_config()?)?;
+            builder
+                .transport(RecordingTransport::new(transport, dir))
+                .build()?
+        },
+        (None, None) => builder.build()?,
+    };
+
+    let Some(matched) = match_provider_for(&input_url) else {
+        return Err(CliError::unavailable(format!(
+            "Unsupported URL: {}. Supported: highlights, books, book categories, book reviews, \
+             highlight links, tags, export, daily review, Reader",
+            input_url
+        ))
+        .into());
+    };
+    let provider = matched.provider;
+
+    // Category lists such as `/books/articles` are the book list with a
+    // fixed category, and Reader lists such as `/later` the document list
+    // with a fixed location, each keeping a sync cursor of their own.
+    let category = category_of(provider);
+    let location = location_of(provider);
+    let list_id = match (category, location) {
+        (Some(_), _) => ReadwiseType::BOOKLIST_ID,
+        (_, Some(_)) => ReadwiseType::READER_ID,
+        _ => provider.id,
+    };
+
+    let highlight_only = options.book_id.is_some()
+        || options.highlighted_after.is_some()
+        || options.highlighted_before.is_some();
+    let book_only = options.category.is_some()
+        || options.source.is_some()
+        || options.last_highlight_after.is_some();
+    let misapplied_filter = match list_id {
+        ReadwiseType::HIGHLIGHTS_ID => book_only,
+        ReadwiseType::BOOKLIST_ID => highlight_only,
+        ReadwiseType::BOOK_ID => book_only || options.book_id.is_some(),
+        _ => highlight_only || book_only || options.updated_before.is_some(),
+    };
+    if misapplied_filter {
+        return Err(
+            CliError::usage(format!("The given filters do not apply to {}", input_url)).into(),
+        );
+    }
+
+    // Filters given in the query string of the input URL, which the
+    // corresponding flags override.
+    let query = matched.query.clone().unwrap_or_default();
+    let url_filters = match list_id {
+        ReadwiseType::HIGHLIGHTS_ID | ReadwiseType::BOOK_ID => HighlightFilter::from_query(&query)
+            .map(|filter| (filter, Default::default(), Default::default())),
+        ReadwiseType::BOOKLIST_ID => BookFilter::from_query(&query)
+            .map(|filter| (Default::default(), filter, Default::default())),
+        ReadwiseType::READER_ID => DocumentFilter::from_query(&query)
+            .map(|filter| (Default::default(), Default::default(), filter)),
+        _ if query.is_empty() => Ok(Default::default()),
+        _ => Err(format!(
+            "Query parameters are not supported for {}",
+            input_url
+        )),
+    };
+    let (url_highlight_filter, url_book_filter, mut document_filter) = match url_filters {
+        Ok(filters) => filters,
+        Err(err) => {
+            return Err(CliError::usage(err).into());
+        },
+    };
+
+    let mut highlight_filter = HighlightFilter {
+        book_id: options.book_id,
+        updated_after: None,
+        updated_before: options.updated_before.clone(),
+        highlighted_after: options.highlighted_after,
+        highlighted_before: options.highlighted_before,
+    }
+    .or(url_highlight_filter);
+    let mut book_filter = BookFilter {
+        category: options.category,
+        source: options.source,
+        updated_after: None,
+        updated_before: options.updated_before,
+        last_highlight_after: options.last_highlight_after,
+    }
+    .or(url_book_filter);
+    if category.is_some() && book_filter.category.is_some() {
+        return Err(CliError::usage(format!("{} already selects a category", input_url)).into());
+    }
+    // A single book, highlight, tag or review shares no sync cursor with
+    // the lists.
+    let single_item = matches!(
+        provider.id,
+        ReadwiseType::BOOK_ID
+            | ReadwiseType::HIGHLIGHT_ID
+            | ReadwiseType::TAG_ID
+            | ReadwiseType::DAILY_REVIEW_ID
+    );
+    let partial_result = single_item
+        || options.page.is_some()
+        || options.limit.is_some()
+        || highlight_filter.is_partial()
+        || book_filter.is_partial()
+        || document_filter.is_partial();
+    book_filter.category = book_filter.category.or(category);
+    document_filter.location = location;
+
+    // Each account keeps its own sync cursors.
+    let sync_key = match &account {
+        Some(account) => format!("{}:{}", account, provider.id),
+        None => provider.id.to_string(),
+    };
+    let mut sync_state = match &options.state {
+        Some(path) => match SyncState::load(path) {
+            Ok(state) => Some(state),
+            // A state file that can be read but not parsed is corrupt.
+            Err(err) if err.root_cause().is::<serde_json::Error>() => {
+                return Err(CliError::new(EX_DATAERR, format!("{:#}", err)).into());
+            },
+            Err(err) => return Err(CliError::new(EX_IOERR, format!("{:#}", err)).into()),
+        },
+        None => None,
+    };
+
+    let last_synced_at = sync_state
+        .as_ref()
+        .filter(|_| !single_item)
+        .and_then(|state| state.last_synced_at(&sync_key))
+        .map(String::from);
+
+    let since = match &options.since {
+        Some(since) => match sync::parse_since(since) {
+            Ok(since) => Some(since),
+            Err(err) => {
+                return Err(CliError::usage(format!("{:#}", err)).into());
+            },
+        },
+        None => highlight_filter
+            .updated_after
+            .clone()
+            .or_else(|| book_filter.updated_after.clone())
+            .or_else(|| document_filter.updated_after.clone())
+            .or_else(|| last_synced_at.clone()),
+    };
+
+    // Starting after the stored cursor skips the changes in between, which
+    // advancing the cursor would then lose for good.
+    let skipped_since = last_synced_at.filter(|last_synced_at| {
+        since
+            .as_deref()
+            .is_some_and(|since| sync::is_later(since, last_synced_at))
+    });
+
+    highlight_filter.updated_after = since.clone();
+    book_filter.updated_after = since.clone();
+    document_filter.updated_after = since.clone();
+
+    let sync_started_at = sync::now();
+
+    let fetched = match list_id {
+        ReadwiseType::HIGHLIGHTS_ID => Fetched::Highlights(match options.page {
+            Some(page) => api.fetch_highlights(options.page_size, Some(page), &highlight_filter)?,
+            None => {
+                api.fetch_all_highlights(options.page_size, options.limit, &highlight_filter)?
+            },
+        }),
+        ReadwiseType::BOOKLIST_ID => Fetched::Books(match options.page {
+            Some(page) => api.fetch_booklist(options.page_size, Some(page), &book_filter)?,
+            None => api.fetch_all_books(options.page_size, options.limit, &book_filter)?,
+        }),
+        ReadwiseType::TAGS_ID => Fetched::Tags(api.fetch_tags()?),
+        ReadwiseType::EXPORT_ID => {
+            Fetched::Export(api.fetch_all_export(since.as_deref(), options.limit)?)
+        },
+        ReadwiseType::BOOK_ID => {
+            let Some(book_id) = matched.param("id").and_then(|id| id.parse().ok()) else {
+                return Err(CliError::usage(format!("Invalid book ID in {}", input_url)).into());
+            };
+            Fetched::Book(Box::new(api.fetch_book_detail(
+                book_id,
+                options.page_size,
+                options.limit,
+                &highlight_filter,
+            )?))
+        },
+        ReadwiseType::HIGHLIGHT_ID => {
+            let Some(highlight_id) = matched.param("id").and_then(|id| id.parse().ok()) else {
+                return Err(
+                    CliError::usage(format!("Invalid highlight ID in {}", input_url)).into(),
+                );
+            };
+            Fetched::Highlight(Box::new(api.fetch_highlight_detail(highlight_id)?))
+        },
+        ReadwiseType::TAG_ID => {
+            let name = matched.param("name").unwrap_or_default();
+            Fetched::Tagged(api.fetch_tagged(name, since.as_deref())?)
+        },
+        ReadwiseType::DAILY_REVIEW_ID => Fetched::DailyReview(api.fetch_daily_review()?),
+        ReadwiseType::READER_ID => {
+            Fetched::Documents(api.fetch_all_documents(&document_filter, options.limit)?)
+        },
+        _ => {
+            return Err(CliError::unavailable(format!(
+                "Unsupported provider type: {:?}",
+                provider.id
+            ))
+            .into());
+        },
+    };
+
+    let output = Output {
+        format: options.output.unwrap_or_default(),
+        list_id,
+        account,
+    };
+    write_output(fetched, &output)?;
+
+    if let (Some(path), Some(state)) = (&options.state, sync_state.as_mut()) {
+        if partial_result {
+            eprintln!(
+                "Not updating sync state: --page, --limit and filters only fetch a partial result"
+            );
+        } else if let Some(last_synced_at) = skipped_since {
+            eprintln!(
+                "Not updating sync state: changes since the last sync at {} were not fetched",
+                last_synced_at
+            );
+        } else {
+            state.set_last_synced_at(&sync_key, sync_started_at);
+            if let Err(err) = state.save(path) {
+                return Err(CliError::new(EX_CANTCREAT, format!("{:#}", err)).into());
+            }
+        }
+    }
+
+    Ok(EX_OK)
+}
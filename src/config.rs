@@ -0,0 +1,140 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::api::token::TokenSource;
+use anyhow::{Context, Result};
+use asimov_module::secrecy::SecretString;
+use serde::{Deserialize, Deserializer};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// The environment variable overriding the config file location.
+pub const CONFIG_PATH_VAR: &str = "READWISE_CONFIG";
+
+/// The environment variable selecting a profile when `--profile` is not given.
+pub const PROFILE_VAR: &str = "READWISE_PROFILE";
+
+/// The profile used when none is selected.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The module's config file, holding settings per account profile.
+///
+/// ```json
+/// {
+///   "profiles": {
+///     "default": { "token_command": "pass show readwise" },
+///     "work": { "token_file": "/run/secrets/readwise", "page_size": 500 }
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConfigFile {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// The settings of one account profile.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Profile {
+    /// The token itself. Prefer `token_file` or `token_command`.
+    #[serde(default, deserialize_with = "deserialize_secret")]
+    pub token: Option<SecretString>,
+    /// A file containing the token.
+    pub token_file: Option<PathBuf>,
+    /// A shell command printing the token on stdout.
+    pub token_command: Option<String>,
+    /// The account identity stamped into JSON-LD `@id`s. Defaults to the
+    /// profile name, except for the default profile.
+    pub account: Option<String>,
+    pub base_url: Option<String>,
+    pub page_size: Option<usize>,
+    /// In seconds.
+    pub connect_timeout: Option<u64>,
+    /// In seconds.
+    pub read_timeout: Option<u64>,
+    pub proxy: Option<String>,
+    pub ca_bundle: Option<PathBuf>,
+    pub max_attempts: Option<u32>,
+    /// In seconds.
+    pub max_retry_wait: Option<u64>,
+    /// Requests per minute by endpoint, e.g. `{ "/highlights/": 20 }`.
+    #[serde(default)]
+    pub rate_limits: BTreeMap<String, u32>,
+}
+
+impl Profile {
+    /// Returns the token sources this profile names, in order of precedence.
+    pub fn token_sources(&self) -> Vec<TokenSource> {
+        let mut sources = Vec::new();
+        if let Some(token) = &self.token {
+            sources.push(TokenSource::Value(token.clone()));
+        }
+        if let Some(path) = &self.token_file {
+            sources.push(TokenSource::File(path.clone()));
+        }
+        if let Some(command) = &self.token_command {
+            sources.push(TokenSource::Command(command.clone()));
+        }
+        sources
+    }
+}
+
+impl ConfigFile {
+    /// Returns `$READWISE_CONFIG`, or else `~/.config/asimov/readwise.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(path) = asimov_module::getenv::var(CONFIG_PATH_VAR) {
+            return Some(PathBuf::from(path));
+        }
+        asimov_module::getenv::home().map(|home| {
+            PathBuf::from(home)
+                .join(".config")
+                .join("asimov")
+                .join("readwise.json")
+        })
+    }
+
+    /// Loads the config file at `path`, or returns an empty config if the
+    /// file does not exist.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config file {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse config file {}", path.display()))
+    }
+
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles.get(name)
+    }
+
+    /// Returns the profile with the given name, failing if there is none.
+    pub fn require_profile(&self, name: &str) -> Result<&Profile> {
+        self.profile(name).with_context(|| {
+            let known: Vec<_> = self.profiles.keys().map(String::as_str).collect();
+            match known.is_empty() {
+                true => format!("unknown profile \"{}\": no profiles are configured", name),
+                false => format!("unknown profile \"{}\" (known: {})", name, known.join(", ")),
+            }
+        })
+    }
+
+    /// Returns the account identity for the named profile, if any.
+    pub fn account(&self, name: &str) -> Option<String> {
+        match self.profile(name).and_then(|p| p.account.clone()) {
+            Some(account) => Some(account),
+            None if name == DEFAULT_PROFILE => None,
+            None => Some(name.to_string()),
+        }
+    }
+}
+
+fn deserialize_secret<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<SecretString>, D::Error> {
+    let value: Option<String> = Option::deserialize(deserializer)?;
+    Ok(value.map(SecretString::from))
+}
@@ -0,0 +1,295 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::api::{
+    ReadwiseError,
+    builder::ReadwiseClientBuilder,
+    token::{self, TokenSource},
+};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// The clock skew beyond which `--since` timestamps become unreliable.
+pub const MAX_CLOCK_SKEW: Duration = Duration::from_secs(60);
+
+/// The environment variables `ureq` reads a proxy from, in order.
+const PROXY_VARS: [&str; 6] = [
+    "ALL_PROXY",
+    "all_proxy",
+    "HTTPS_PROXY",
+    "https_proxy",
+    "HTTP_PROXY",
+    "http_proxy",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Warn,
+    Fail,
+    Skip,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Status::Ok => "ok",
+            Status::Warn => "warn",
+            Status::Fail => "FAIL",
+            Status::Skip => "skip",
+        };
+        f.pad(label)
+    }
+}
+
+/// The outcome of one diagnostic check.
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub message: String,
+    /// The error behind a failed check, which decides the exit code.
+    #[serde(skip)]
+    pub error: Option<ReadwiseError>,
+}
+
+impl Check {
+    fn new(name: &'static str, status: Status, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            message: message.into(),
+            error: None,
+        }
+    }
+
+    fn failed(name: &'static str, error: ReadwiseError) -> Self {
+        Self {
+            name,
+            status: Status::Fail,
+            message: error.to_string(),
+            error: Some(error),
+        }
+    }
+}
+
+/// The diagnostics report, printed as text or serialized as JSON.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub ok: bool,
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    fn new(checks: Vec<Check>) -> Self {
+        Self {
+            ok: checks.iter().all(|check| check.status != Status::Fail),
+            checks,
+        }
+    }
+
+    pub fn check(&self, name: &str) -> Option<&Check> {
+        self.checks.iter().find(|check| check.name == name)
+    }
+
+    /// Returns the exit code matching the first failed check.
+    #[cfg(feature = "cli")]
+    pub fn exit_code(&self) -> clientele::SysexitsError {
+        use clientele::SysexitsError;
+        match self
+            .checks
+            .iter()
+            .find(|check| check.status == Status::Fail)
+        {
+            Some(Check {
+                error: Some(error), ..
+            }) => SysexitsError::from(error),
+            Some(_) => SysexitsError::EX_CONFIG,
+            None => SysexitsError::EX_OK,
+        }
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for check in &self.checks {
+            writeln!(
+                f,
+                "[{:<4}] {:<12} {}",
+                check.status, check.name, check.message
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Diagnoses the module's setup: the token and where it came from, the
+/// client settings, the proxy, whether the API is reachable and accepts the
+/// token, the clock skew against Readwise, and the installed module manifest.
+pub fn run(builder: ReadwiseClientBuilder, token_sources: &[TokenSource]) -> Report {
+    let mut checks = vec![check_manifest()];
+
+    let builder = match token::find_first(token_sources) {
+        Ok((token, source)) => {
+            checks.push(Check::new(
+                "token",
+                Status::Ok,
+                format!("found in {}", source),
+            ));
+            builder.resolved_token(token, source)
+        },
+        Err(error) => {
+            checks.push(Check::failed("token", error));
+            return Report::new(checks);
+        },
+    };
+
+    let config = match builder.build_config() {
+        Ok(config) => config,
+        Err(error) => {
+            checks.push(Check::failed("config", error));
+            return Report::new(checks);
+        },
+    };
+    checks.push(check_proxy(config.proxy.as_deref()));
+
+    let client = match builder.build() {
+        Ok(client) => client,
+        Err(error) => {
+            checks.push(Check::failed("config", error));
+            return Report::new(checks);
+        },
+    };
+
+    let started_at = Instant::now();
+    let result = client.verify_token();
+    let elapsed = started_at.elapsed();
+
+    let reachable = format!("{} answered in {} ms", config.base_url, elapsed.as_millis());
+    match result {
+        Ok(status) => {
+            checks.push(Check::new("reachability", Status::Ok, reachable));
+            checks.push(Check::new("auth", Status::Ok, "token accepted by Readwise"));
+            checks.push(check_clock(status.server_date.as_deref()));
+        },
+        Err(error @ ReadwiseError::Transport(_)) => {
+            checks.push(Check::failed("reachability", error));
+            checks.push(Check::new("auth", Status::Skip, "Readwise is unreachable"));
+        },
+        Err(error) => {
+            checks.push(Check::new("reachability", Status::Ok, reachable));
+            checks.push(Check::failed("auth", error));
+        },
+    }
+
+    Report::new(checks)
+}
+
+fn check_proxy(configured: Option<&str>) -> Check {
+    if let Some(proxy) = configured {
+        return Check::new(
+            "proxy",
+            Status::Ok,
+            format!("using {}", redact_proxy(proxy)),
+        );
+    }
+    for name in PROXY_VARS {
+        let Some(proxy) = asimov_module::getenv::var(name) else {
+            continue;
+        };
+        return match ureq::Proxy::new(&proxy) {
+            Ok(_) => Check::new(
+                "proxy",
+                Status::Ok,
+                format!("using {} from ${}", redact_proxy(&proxy), name),
+            ),
+            Err(error) => Check::new(
+                "proxy",
+                Status::Warn,
+                format!("ignoring invalid ${}: {}", name, error),
+            ),
+        };
+    }
+    Check::new("proxy", Status::Ok, "none, connecting directly")
+}
+
+/// Hides the password of a proxy URL.
+fn redact_proxy(proxy: &str) -> String {
+    match url::Url::parse(proxy) {
+        Ok(mut url) if url.password().is_some() => {
+            let _ = url.set_password(Some(crate::api::recording::REDACTED));
+            url.to_string()
+        },
+        _ => proxy.to_string(),
+    }
+}
+
+fn check_clock(server_date: Option<&str>) -> Check {
+    let Some(server_date) =
+        server_date.and_then(|date| chrono::DateTime::parse_from_rfc2822(date).ok())
+    else {
+        return Check::new("clock", Status::Skip, "Readwise sent no Date header");
+    };
+
+    let skew = chrono::Utc::now().signed_duration_since(server_date);
+    let seconds = skew.num_seconds();
+    if seconds.unsigned_abs() <= MAX_CLOCK_SKEW.as_secs() {
+        return Check::new(
+            "clock",
+            Status::Ok,
+            format!("within {}s of Readwise", MAX_CLOCK_SKEW.as_secs()),
+        );
+    }
+    let direction = if seconds > 0 { "ahead of" } else { "behind" };
+    Check::new(
+        "clock",
+        Status::Warn,
+        format!(
+            "local clock is {}s {} Readwise; --since and sync state may skip or repeat items",
+            seconds.unsigned_abs(),
+            direction
+        ),
+    )
+}
+
+fn check_manifest() -> Check {
+    use crate::providers::readwise::URL_PREFIX_TO_PROVIDER;
+
+    let manifest = match asimov_module::ModuleManifest::read_manifest("readwise") {
+        Ok(manifest) => manifest,
+        Err(error) => {
+            return Check::new(
+                "manifest",
+                Status::Warn,
+                format!(
+                    "no installed module manifest ({}); run `asimov module install readwise`",
+                    error
+                ),
+            );
+        },
+    };
+
+    let missing: Vec<_> = URL_PREFIX_TO_PROVIDER
+        .iter()
+        .map(|(prefix, _)| *prefix)
+        .filter(|prefix| !manifest.handles.url_prefixes.iter().any(|p| p == prefix))
+        .collect();
+    if !missing.is_empty() {
+        return Check::new(
+            "manifest",
+            Status::Warn,
+            format!(
+                "installed manifest is out of date, missing {}",
+                missing.join(", ")
+            ),
+        );
+    }
+    Check::new(
+        "manifest",
+        Status::Ok,
+        format!(
+            "installed, handling {} URL prefixes",
+            manifest.handles.url_prefixes.len()
+        ),
+    )
+}
@@ -10,7 +10,11 @@ pub fn readwise() -> &'static JsonFilter {
 pub fn books() -> &'static JsonFilter {
     use std::sync::OnceLock;
     static ONCE: OnceLock<JsonFilter> = OnceLock::new();
-    ONCE.get_or_init(|| include_str!("jq/books.jq").parse().unwrap())
+    ONCE.get_or_init(|| {
+        concat!(include_str!("jq/lib.jq"), include_str!("jq/books.jq"))
+            .parse()
+            .unwrap()
+    })
 }
 
 pub fn tags() -> &'static JsonFilter {
@@ -18,3 +22,53 @@ pub fn tags() -> &'static JsonFilter {
     static ONCE: OnceLock<JsonFilter> = OnceLock::new();
     ONCE.get_or_init(|| include_str!("jq/tags.jq").parse().unwrap())
 }
+
+pub fn export() -> &'static JsonFilter {
+    use std::sync::OnceLock;
+    static ONCE: OnceLock<JsonFilter> = OnceLock::new();
+    ONCE.get_or_init(|| {
+        concat!(include_str!("jq/lib.jq"), include_str!("jq/export.jq"))
+            .parse()
+            .unwrap()
+    })
+}
+
+pub fn book() -> &'static JsonFilter {
+    use std::sync::OnceLock;
+    static ONCE: OnceLock<JsonFilter> = OnceLock::new();
+    ONCE.get_or_init(|| {
+        concat!(include_str!("jq/lib.jq"), include_str!("jq/book.jq"))
+            .parse()
+            .unwrap()
+    })
+}
+
+pub fn highlight() -> &'static JsonFilter {
+    use std::sync::OnceLock;
+    static ONCE: OnceLock<JsonFilter> = OnceLock::new();
+    ONCE.get_or_init(|| {
+        concat!(include_str!("jq/lib.jq"), include_str!("jq/highlight.jq"))
+            .parse()
+            .unwrap()
+    })
+}
+
+/// Combines the output of [`readwise`] and [`books`] for the highlights and
+/// books carrying one tag, given as `{name, highlights, books}`.
+pub fn tag() -> &'static JsonFilter {
+    use std::sync::OnceLock;
+    static ONCE: OnceLock<JsonFilter> = OnceLock::new();
+    ONCE.get_or_init(|| include_str!("jq/tag.jq").parse().unwrap())
+}
+
+pub fn review() -> &'static JsonFilter {
+    use std::sync::OnceLock;
+    static ONCE: OnceLock<JsonFilter> = OnceLock::new();
+    ONCE.get_or_init(|| include_str!("jq/review.jq").parse().unwrap())
+}
+
+pub fn reader() -> &'static JsonFilter {
+    use std::sync::OnceLock;
+    static ONCE: OnceLock<JsonFilter> = OnceLock::new();
+    ONCE.get_or_init(|| include_str!("jq/reader.jq").parse().unwrap())
+}
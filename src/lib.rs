@@ -3,20 +3,22 @@
 #![forbid(unsafe_code)]
 
 pub mod api;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod config;
+pub mod doctor;
 pub mod jq;
 pub mod output;
 pub mod providers;
+pub mod sync;
 
-pub use providers::Provider;
+pub use providers::{Provider, ProviderMatch};
 
 pub fn find_provider_for(url: impl AsRef<str>) -> Option<&'static Provider> {
-    let url = url.as_ref();
-    // Iterate in reverse to ensure that more specific URL patterns are matched first.
-    // This relies on URL_PREFIX_TO_PROVIDER being ordered from least to most specific.
-    for (url_pattern, provider) in providers::readwise::URL_PREFIX_TO_PROVIDER.iter().rev() {
-        if url.starts_with(url_pattern) {
-            return Some(provider);
-        }
-    }
-    None
+    match_provider_for(url).map(|matched| matched.provider)
+}
+
+/// Matches a URL to its provider, capturing path parameters and the query.
+pub fn match_provider_for(url: impl AsRef<str>) -> Option<ProviderMatch> {
+    providers::matching::match_url(url.as_ref())
 }
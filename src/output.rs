@@ -3,15 +3,146 @@
 use clap::ValueEnum;
 use serde::Serialize;
 
-#[derive(Debug, Clone, ValueEnum)]
+#[derive(Debug, Clone, Default, ValueEnum)]
 pub enum OutputFormat {
+    #[default]
     Json,
     Jsonl,
 }
 
-impl Default for OutputFormat {
-    fn default() -> Self {
-        OutputFormat::Json
+#[derive(Debug, Clone, Default, ValueEnum)]
+pub enum ErrorFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// A failure of the command line programs themselves, such as a missing
+/// argument or an unusable config file, with the exit code it maps to.
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct CliError {
+    pub exit_code: clientele::SysexitsError,
+    pub message: String,
+}
+
+impl CliError {
+    pub fn new(exit_code: clientele::SysexitsError, message: impl Into<String>) -> Self {
+        Self {
+            exit_code,
+            message: message.into(),
+        }
+    }
+
+    pub fn usage(message: impl Into<String>) -> Self {
+        Self::new(clientele::SysexitsError::EX_USAGE, message)
+    }
+
+    pub fn config(message: impl Into<String>) -> Self {
+        Self::new(clientele::SysexitsError::EX_CONFIG, message)
+    }
+
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self::new(clientele::SysexitsError::EX_UNAVAILABLE, message)
+    }
+
+    /// A stable, machine-readable name for the error class.
+    pub fn kind(&self) -> &'static str {
+        use clientele::SysexitsError::*;
+        match self.exit_code {
+            EX_USAGE => "usage",
+            EX_CONFIG => "config",
+            EX_IOERR | EX_CANTCREAT => "io",
+            EX_DATAERR => "data",
+            EX_UNAVAILABLE => "unsupported",
+            _ => "other",
+        }
+    }
+}
+
+/// Reports a fatal error on stderr in the requested format, and returns the
+/// exit code matching its cause.
+pub fn write_error(
+    error: &(dyn std::error::Error + 'static),
+    format: &ErrorFormat,
+) -> clientele::SysexitsError {
+    use crate::api::ReadwiseError;
+    use clientele::SysexitsError;
+
+    let readwise_error = error.downcast_ref::<ReadwiseError>();
+    let cli_error = error.downcast_ref::<CliError>();
+    let exit_code = match (readwise_error, cli_error) {
+        (Some(error), _) => SysexitsError::from(error),
+        (_, Some(error)) => error.exit_code,
+        _ => match error.downcast_ref::<std::io::Error>() {
+            Some(error) => SysexitsError::from(error),
+            None => SysexitsError::EX_SOFTWARE,
+        },
+    };
+    let kind = match (readwise_error, cli_error) {
+        (Some(error), _) => error.kind(),
+        (_, Some(error)) => error.kind(),
+        _ => "other",
+    };
+
+    match format {
+        ErrorFormat::Text => eprintln!("Error: {}", error),
+        ErrorFormat::Json => {
+            let mut object = serde_json::json!({
+                "error": kind,
+                "message": error.to_string(),
+                "exit_code": exit_code.as_i32(),
+                "exit_name": exit_code.name(),
+                "retryable": readwise_error.is_some_and(|e| e.is_retryable()),
+            });
+            match readwise_error {
+                Some(ReadwiseError::RateLimited {
+                    retry_after: Some(wait),
+                }) => object["retry_after"] = wait.as_secs().into(),
+                Some(ReadwiseError::Http { status, .. }) => object["status"] = (*status).into(),
+                _ => {},
+            }
+            eprintln!("{}", object);
+        },
+    }
+
+    exit_code
+}
+
+/// Stamps an account identity into every Readwise and Reader `@id` of a
+/// JSON-LD document, so that resources imported from different accounts never
+/// collide, e.g. `https://readwise.io/open/123?account=work`.
+///
+/// Term definitions under `@context` are left alone.
+pub fn stamp_account(json_ld: &mut serde_json::Value, account: &str) {
+    use serde_json::Value;
+
+    let query: String = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("account", account)
+        .finish();
+
+    match json_ld {
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                match (key.as_str(), value) {
+                    ("@context", _) => {},
+                    ("@id", Value::String(id))
+                        if id.starts_with("https://readwise.io/")
+                            || id.starts_with("https://read.readwise.io/") =>
+                    {
+                        let separator = if id.contains('?') { '&' } else { '?' };
+                        *id = format!("{}{}{}", id, separator, query);
+                    },
+                    (_, value) => stamp_account(value, account),
+                }
+            }
+        },
+        Value::Array(items) => {
+            for item in items {
+                stamp_account(item, account);
+            }
+        },
+        _ => {},
     }
 }
 
@@ -39,24 +170,36 @@ pub fn write_jsonl_from_jsonld(
 ) -> Result<(), Box<dyn std::error::Error>> {
     use crate::api::types::ReadwiseType;
 
-    let items = match provider_id {
-        ReadwiseType::HIGHLIGHTS_ID => json_ld
-            .get("highlights")
-            .and_then(|h| h.get("items"))
-            .and_then(|i| i.as_array()),
-        ReadwiseType::BOOKLIST_ID => json_ld
-            .get("books")
-            .and_then(|b| b.get("items"))
-            .and_then(|i| i.as_array()),
-        ReadwiseType::TAGS_ID => json_ld
-            .get("tags")
-            .and_then(|t| t.get("items"))
-            .and_then(|i| i.as_array()),
-        _ => None,
+    // A single book or highlight is one node already.
+    if matches!(
+        provider_id,
+        ReadwiseType::BOOK_ID | ReadwiseType::HIGHLIGHT_ID
+    ) {
+        println!("{}", serde_json::to_string(json_ld)?);
+        return Ok(());
+    }
+
+    let collections: &[&str] = match provider_id {
+        ReadwiseType::HIGHLIGHTS_ID => &["highlights"],
+        ReadwiseType::BOOKLIST_ID | ReadwiseType::EXPORT_ID => &["books"],
+        ReadwiseType::TAGS_ID => &["tags"],
+        ReadwiseType::TAG_ID => &["highlights", "books"],
+        ReadwiseType::DAILY_REVIEW_ID => &["highlights"],
+        ReadwiseType::READER_ID => &["documents"],
+        _ => &[],
     };
+    let items: Vec<_> = collections
+        .iter()
+        .filter_map(|collection| {
+            json_ld
+                .get(collection)
+                .and_then(|c| c.get("items"))
+                .and_then(|i| i.as_array())
+        })
+        .collect();
 
-    if let Some(items_array) = items {
-        for item in items_array {
+    if !items.is_empty() {
+        for item in items.into_iter().flatten() {
             let line = serde_json::to_string(item)?;
             println!("{}", line);
         }
@@ -0,0 +1,109 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::providers::{
+    Provider,
+    readwise::{READER_URL_PATTERNS, URL_PATTERNS},
+};
+use std::collections::BTreeMap;
+
+/// The hosts Readwise URLs are accepted on.
+const HOSTS: [&str; 2] = ["readwise.io", "www.readwise.io"];
+
+/// The hosts Reader URLs are accepted on.
+const READER_HOSTS: [&str; 1] = ["read.readwise.io"];
+
+/// A provider matched against an input URL.
+#[derive(Clone, Debug)]
+pub struct ProviderMatch {
+    pub provider: &'static Provider,
+    /// The path parameters captured by the provider's URL pattern, such as
+    /// `id` in `/bookreview/{id}`, percent-decoded.
+    pub params: BTreeMap<&'static str, String>,
+    /// The query string of the input URL, still percent-encoded.
+    pub query: Option<String>,
+}
+
+impl ProviderMatch {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
+    }
+}
+
+/// Matches an input URL against the provider URL patterns.
+///
+/// The scheme may be `https`, `http` or omitted, the host `readwise.io` or
+/// `www.readwise.io`, or `read.readwise.io` for Reader, and empty path
+/// segments such as a trailing slash are ignored. Path segments are compared
+/// whole, so `/booksXYZ` does not match `/books`. When several patterns
+/// match, the one with the most literal segments wins.
+pub fn match_url(url: &str) -> Option<ProviderMatch> {
+    let url = url.trim();
+    let url = match url::Url::parse(url) {
+        Ok(url) => url,
+        Err(url::ParseError::RelativeUrlWithoutBase) => {
+            url::Url::parse(&format!("https://{}", url)).ok()?
+        },
+        Err(_) => return None,
+    };
+
+    if !matches!(url.scheme(), "http" | "https") || url.port().is_some() {
+        return None;
+    }
+    let patterns: &[(&str, &Provider)] = match url.host_str()? {
+        host if HOSTS.contains(&host) => &URL_PATTERNS,
+        host if READER_HOSTS.contains(&host) => &READER_URL_PATTERNS,
+        _ => return None,
+    };
+
+    let segments: Vec<_> = url
+        .path_segments()?
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    patterns
+        .iter()
+        .filter_map(|(pattern, provider)| {
+            let params = match_segments(pattern, &segments)?;
+            Some((literal_segments(pattern), provider, params))
+        })
+        .max_by_key(|(literals, _, _)| *literals)
+        .map(|(_, provider, params)| ProviderMatch {
+            provider,
+            params,
+            query: url.query().map(String::from),
+        })
+}
+
+/// Matches path segments against a pattern like `/books/{id}`, returning
+/// the captured parameters.
+fn match_segments(
+    pattern: &'static str,
+    segments: &[&str],
+) -> Option<BTreeMap<&'static str, String>> {
+    let pattern: Vec<_> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    if pattern.len() != segments.len() {
+        return None;
+    }
+
+    let mut params = BTreeMap::new();
+    for (expected, actual) in pattern.into_iter().zip(segments) {
+        match expected.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(name) => {
+                let value = percent_encoding::percent_decode_str(actual)
+                    .decode_utf8()
+                    .ok()?;
+                params.insert(name, value.into_owned());
+            },
+            None if expected == *actual => {},
+            None => return None,
+        }
+    }
+    Some(params)
+}
+
+fn literal_segments(pattern: &str) -> usize {
+    pattern
+        .split('/')
+        .filter(|s| !s.is_empty() && !s.starts_with('{'))
+        .count()
+}
@@ -1,4 +1,6 @@
+pub mod matching;
 pub mod provider;
 pub mod readwise;
 
+pub use matching::ProviderMatch;
 pub use provider::*;
@@ -1,5 +1,6 @@
 // This is free and unencumbered software released into the public domain.
 
+use crate::api::filter::{BookCategory, ReaderLocation};
 use crate::api::types::ReadwiseType;
 use crate::providers::provider::Provider;
 
@@ -18,8 +19,140 @@ pub static READWISE_TAGS: Provider = Provider {
     url: "https://readwise.io/tags",
 };
 
-pub static URL_PREFIX_TO_PROVIDER: [(&str, &'static Provider); 3] = [
+pub static READWISE_EXPORT: Provider = Provider {
+    id: ReadwiseType::EXPORT_ID,
+    url: "https://readwise.io/export",
+};
+
+pub static READWISE_BOOK: Provider = Provider {
+    id: ReadwiseType::BOOK_ID,
+    url: "https://readwise.io/bookreview",
+};
+
+pub static READWISE_HIGHLIGHT: Provider = Provider {
+    id: ReadwiseType::HIGHLIGHT_ID,
+    url: "https://readwise.io/open",
+};
+
+pub static READWISE_TAG: Provider = Provider {
+    id: ReadwiseType::TAG_ID,
+    url: "https://readwise.io/tags",
+};
+
+pub static READWISE_DAILY_REVIEW: Provider = Provider {
+    id: ReadwiseType::DAILY_REVIEW_ID,
+    url: "https://readwise.io/dailyreview",
+};
+
+pub static READWISE_ARTICLES: Provider = Provider {
+    id: ReadwiseType::ARTICLES_ID,
+    url: "https://readwise.io/books/articles",
+};
+
+pub static READWISE_TWEETS: Provider = Provider {
+    id: ReadwiseType::TWEETS_ID,
+    url: "https://readwise.io/books/tweets",
+};
+
+pub static READWISE_PODCASTS: Provider = Provider {
+    id: ReadwiseType::PODCASTS_ID,
+    url: "https://readwise.io/books/podcasts",
+};
+
+pub static READWISE_SUPPLEMENTALS: Provider = Provider {
+    id: ReadwiseType::SUPPLEMENTALS_ID,
+    url: "https://readwise.io/books/supplementals",
+};
+
+/// The book list providers scoped to one category.
+pub static CATEGORY_PROVIDERS: [(&Provider, BookCategory); 4] = [
+    (&READWISE_ARTICLES, BookCategory::Articles),
+    (&READWISE_TWEETS, BookCategory::Tweets),
+    (&READWISE_PODCASTS, BookCategory::Podcasts),
+    (&READWISE_SUPPLEMENTALS, BookCategory::Supplementals),
+];
+
+/// Returns the category a book list provider is scoped to, if any.
+pub fn category_of(provider: &Provider) -> Option<BookCategory> {
+    CATEGORY_PROVIDERS
+        .iter()
+        .find(|(scoped, _)| scoped.id == provider.id)
+        .map(|(_, category)| *category)
+}
+
+pub static READWISE_READER_NEW: Provider = Provider {
+    id: ReadwiseType::READER_NEW_ID,
+    url: "https://read.readwise.io/new",
+};
+
+pub static READWISE_READER_LATER: Provider = Provider {
+    id: ReadwiseType::READER_LATER_ID,
+    url: "https://read.readwise.io/later",
+};
+
+pub static READWISE_READER_ARCHIVE: Provider = Provider {
+    id: ReadwiseType::READER_ARCHIVE_ID,
+    url: "https://read.readwise.io/archive",
+};
+
+pub static READWISE_READER_FEED: Provider = Provider {
+    id: ReadwiseType::READER_FEED_ID,
+    url: "https://read.readwise.io/feed",
+};
+
+/// The Reader document list providers, each scoped to one location.
+pub static LOCATION_PROVIDERS: [(&Provider, ReaderLocation); 4] = [
+    (&READWISE_READER_NEW, ReaderLocation::New),
+    (&READWISE_READER_LATER, ReaderLocation::Later),
+    (&READWISE_READER_ARCHIVE, ReaderLocation::Archive),
+    (&READWISE_READER_FEED, ReaderLocation::Feed),
+];
+
+/// Returns the location a Reader document list provider is scoped to, if
+/// any.
+pub fn location_of(provider: &Provider) -> Option<ReaderLocation> {
+    LOCATION_PROVIDERS
+        .iter()
+        .find(|(scoped, _)| scoped.id == provider.id)
+        .map(|(_, location)| *location)
+}
+
+/// The path patterns of Readwise URLs, with `{name}` capturing a segment.
+pub static URL_PATTERNS: [(&str, &Provider); 13] = [
+    ("/highlights", &READWISE_HIGHLIGHTS),
+    ("/books", &READWISE_BOOKLIST),
+    ("/tags", &READWISE_TAGS),
+    ("/export", &READWISE_EXPORT),
+    ("/bookreview/{id}", &READWISE_BOOK),
+    ("/books/{id}", &READWISE_BOOK),
+    ("/books/articles", &READWISE_ARTICLES),
+    ("/books/tweets", &READWISE_TWEETS),
+    ("/books/podcasts", &READWISE_PODCASTS),
+    ("/books/supplementals", &READWISE_SUPPLEMENTALS),
+    ("/dailyreview", &READWISE_DAILY_REVIEW),
+    ("/open/{id}", &READWISE_HIGHLIGHT),
+    ("/tags/{name}", &READWISE_TAG),
+];
+
+/// The path patterns of Reader URLs on `read.readwise.io`.
+pub static READER_URL_PATTERNS: [(&str, &Provider); 4] = [
+    ("/new", &READWISE_READER_NEW),
+    ("/later", &READWISE_READER_LATER),
+    ("/archive", &READWISE_READER_ARCHIVE),
+    ("/feed", &READWISE_READER_FEED),
+];
+
+/// The URL prefixes listed in the module manifest.
+pub static URL_PREFIX_TO_PROVIDER: [(&str, &Provider); 11] = [
     ("https://readwise.io/highlights", &READWISE_HIGHLIGHTS),
     ("https://readwise.io/books", &READWISE_BOOKLIST),
     ("https://readwise.io/tags", &READWISE_TAGS),
+    ("https://readwise.io/export", &READWISE_EXPORT),
+    ("https://readwise.io/bookreview", &READWISE_BOOK),
+    ("https://readwise.io/open", &READWISE_HIGHLIGHT),
+    ("https://readwise.io/dailyreview", &READWISE_DAILY_REVIEW),
+    ("https://read.readwise.io/new", &READWISE_READER_NEW),
+    ("https://read.readwise.io/later", &READWISE_READER_LATER),
+    ("https://read.readwise.io/archive", &READWISE_READER_ARCHIVE),
+    ("https://read.readwise.io/feed", &READWISE_READER_FEED),
 ];
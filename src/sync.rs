@@ -0,0 +1,91 @@
+// This is free and unencumbered software released into the public domain.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
+
+/// Persisted incremental sync state, keyed by provider ID.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    #[serde(flatten)]
+    pub providers: BTreeMap<String, SyncCursor>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncCursor {
+    pub last_synced_at: String,
+}
+
+impl SyncState {
+    /// Loads the state file at `path`, or returns an empty state if the
+    /// file does not exist yet.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read sync state from {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse sync state in {}", path.display()))
+    }
+
+    /// Writes the state file atomically, creating parent directories as needed.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let write = || -> std::io::Result<()> {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            let temp_path = path.with_extension("tmp");
+            std::fs::write(&temp_path, serde_json::to_string_pretty(self)?)?;
+            std::fs::rename(&temp_path, path)
+        };
+        write().with_context(|| format!("failed to write sync state to {}", path.display()))
+    }
+
+    pub fn last_synced_at(&self, provider_id: &str) -> Option<&str> {
+        self.providers
+            .get(provider_id)
+            .map(|cursor| cursor.last_synced_at.as_str())
+    }
+
+    pub fn set_last_synced_at(&mut self, provider_id: &str, timestamp: impl Into<String>) {
+        self.providers.insert(
+            provider_id.to_string(),
+            SyncCursor {
+                last_synced_at: timestamp.into(),
+            },
+        );
+    }
+}
+
+/// Validates an RFC 3339 timestamp and normalizes it to UTC.
+pub fn parse_since(input: &str) -> Result<String> {
+    let timestamp = DateTime::parse_from_rfc3339(input)
+        .with_context(|| format!("invalid RFC 3339 timestamp: {}", input))?;
+    Ok(format_timestamp(timestamp.with_timezone(&Utc)))
+}
+
+/// Returns whether an RFC 3339 timestamp is later than another one. Invalid
+/// timestamps are compared as strings.
+pub fn is_later(timestamp: &str, other: &str) -> bool {
+    match (
+        DateTime::parse_from_rfc3339(timestamp),
+        DateTime::parse_from_rfc3339(other),
+    ) {
+        (Ok(timestamp), Ok(other)) => timestamp > other,
+        _ => timestamp > other,
+    }
+}
+
+/// Returns the current time as an RFC 3339 timestamp, for recording the
+/// start of a sync.
+pub fn now() -> String {
+    format_timestamp(Utc::now())
+}
+
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
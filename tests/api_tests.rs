@@ -1,8 +1,17 @@
 // This is free and unencumbered software released into the public domain.
 
+use asimov_module::secrecy::ExposeSecret;
 use asimov_readwise_module::{
-    api::readwise::ReadwiseConfig, api::types::ReadwiseType, find_provider_for,
+    api::ReadwiseError,
+    api::filter::{BookCategory, ReaderLocation},
+    api::readwise::{ReadwiseConfig, RetryPolicy, collect_tagged, summarize_tags},
+    api::types::{ExportBook, ReadwiseType},
+    config::ConfigFile,
+    find_provider_for, match_provider_for,
+    output::stamp_account,
+    providers::readwise::{category_of, location_of},
 };
+use std::time::Duration;
 
 #[test]
 fn test_find_provider_for_highlights() {
@@ -28,6 +37,14 @@ fn test_find_provider_for_tags() {
     assert_eq!(provider.unwrap().id, ReadwiseType::TAGS_ID);
 }
 
+#[test]
+fn test_find_provider_for_export() {
+    let url = "https://readwise.io/export";
+    let provider = find_provider_for(url);
+    assert!(provider.is_some());
+    assert_eq!(provider.unwrap().id, ReadwiseType::EXPORT_ID);
+}
+
 #[test]
 fn test_find_provider_for_unsupported_url() {
     let url = "https://example.com/api/books";
@@ -35,6 +52,139 @@ fn test_find_provider_for_unsupported_url() {
     assert!(provider.is_none());
 }
 
+#[test]
+fn test_find_provider_for_accepted_url_forms() {
+    for url in [
+        "https://readwise.io/books",
+        "http://readwise.io/books",
+        "https://www.readwise.io/books",
+        "https://readwise.io/books/",
+        "https://READWISE.io/books",
+        "readwise.io/books",
+        "www.readwise.io/books/",
+        "https://readwise.io//books",
+        "https://readwise.io/books#top",
+        "  https://readwise.io/books  ",
+    ] {
+        let provider = find_provider_for(url);
+        assert_eq!(
+            provider.map(|p| p.id),
+            Some(ReadwiseType::BOOKLIST_ID),
+            "{}",
+            url
+        );
+    }
+}
+
+#[test]
+fn test_find_provider_for_rejected_url_forms() {
+    for url in [
+        "https://readwise.io/booksXYZ",
+        "https://readwise.io/books/extra/segments",
+        "https://readwise.io/api/v2/books/",
+        "https://read.readwise.io/books",
+        "https://readwise.io.example.com/books",
+        "https://readwise.io:8080/books",
+        "ftp://readwise.io/books",
+        "https://readwise.io/",
+        "not a url",
+    ] {
+        assert!(find_provider_for(url).is_none(), "{}", url);
+    }
+}
+
+#[test]
+fn test_match_provider_for_keeps_query() {
+    let matched =
+        match_provider_for("https://www.readwise.io/highlights/?book_id=123&page_size=10").unwrap();
+    assert_eq!(matched.provider.id, ReadwiseType::HIGHLIGHTS_ID);
+    assert_eq!(matched.query.as_deref(), Some("book_id=123&page_size=10"));
+    assert!(matched.params.is_empty());
+
+    let matched = match_provider_for("https://readwise.io/tags").unwrap();
+    assert_eq!(matched.query, None);
+}
+
+#[test]
+fn test_match_provider_for_single_book() {
+    for url in [
+        "https://readwise.io/bookreview/123",
+        "https://readwise.io/bookreview/123/",
+        "https://readwise.io/books/123",
+    ] {
+        let matched = match_provider_for(url).unwrap();
+        assert_eq!(matched.provider.id, ReadwiseType::BOOK_ID, "{}", url);
+        assert_eq!(matched.param("id"), Some("123"), "{}", url);
+    }
+
+    let matched = match_provider_for("https://readwise.io/books/").unwrap();
+    assert_eq!(matched.provider.id, ReadwiseType::BOOKLIST_ID);
+    assert!(find_provider_for("https://readwise.io/bookreview").is_none());
+}
+
+#[test]
+fn test_match_provider_for_highlight_link() {
+    let matched = match_provider_for("https://readwise.io/open/987654").unwrap();
+    assert_eq!(matched.provider.id, ReadwiseType::HIGHLIGHT_ID);
+    assert_eq!(matched.param("id"), Some("987654"));
+    assert!(find_provider_for("https://readwise.io/open").is_none());
+}
+
+#[test]
+fn test_match_provider_for_tag() {
+    let matched = match_provider_for("https://readwise.io/tags/to%20read").unwrap();
+    assert_eq!(matched.provider.id, ReadwiseType::TAG_ID);
+    assert_eq!(matched.param("name"), Some("to read"));
+
+    let matched = match_provider_for("https://readwise.io/tags/").unwrap();
+    assert_eq!(matched.provider.id, ReadwiseType::TAGS_ID);
+}
+
+#[test]
+fn test_match_provider_for_book_categories() {
+    for (url, category) in [
+        ("https://readwise.io/books/articles", BookCategory::Articles),
+        ("https://readwise.io/books/tweets/", BookCategory::Tweets),
+        ("readwise.io/books/podcasts", BookCategory::Podcasts),
+        (
+            "https://readwise.io/books/supplementals",
+            BookCategory::Supplementals,
+        ),
+    ] {
+        let provider = find_provider_for(url).unwrap();
+        assert_eq!(category_of(provider), Some(category), "{}", url);
+    }
+
+    let provider = find_provider_for("https://readwise.io/books/123").unwrap();
+    assert_eq!(provider.id, ReadwiseType::BOOK_ID);
+    assert_eq!(category_of(provider), None);
+}
+
+#[test]
+fn test_find_provider_for_daily_review() {
+    let provider = find_provider_for("https://readwise.io/dailyreview/").unwrap();
+    assert_eq!(provider.id, ReadwiseType::DAILY_REVIEW_ID);
+}
+
+#[test]
+fn test_match_provider_for_reader_locations() {
+    for (url, location) in [
+        ("https://read.readwise.io/new", ReaderLocation::New),
+        ("https://read.readwise.io/later/", ReaderLocation::Later),
+        ("read.readwise.io/archive", ReaderLocation::Archive),
+        (
+            "https://read.readwise.io/feed?category=rss",
+            ReaderLocation::Feed,
+        ),
+    ] {
+        let provider = find_provider_for(url).unwrap();
+        assert_eq!(location_of(provider), Some(location), "{}", url);
+    }
+
+    assert!(find_provider_for("https://readwise.io/later").is_none());
+    assert!(find_provider_for("https://read.readwise.io/highlights").is_none());
+}
+
 #[test]
 fn test_provider_url() {
     let url = "https://readwise.io/highlights";
@@ -65,11 +215,210 @@ fn test_readwise_type_as_str() {
     assert_eq!(ReadwiseType::Highlights.as_str(), "readwise-highlights");
     assert_eq!(ReadwiseType::Booklist.as_str(), "readwise-booklist");
     assert_eq!(ReadwiseType::Tags.as_str(), "readwise-tags");
+    assert_eq!(ReadwiseType::Export.as_str(), "readwise-export");
+    assert_eq!(ReadwiseType::Book.as_str(), "readwise-book");
+    assert_eq!(ReadwiseType::Highlight.as_str(), "readwise-highlight");
+    assert_eq!(ReadwiseType::Tag.as_str(), "readwise-tag");
+    assert_eq!(ReadwiseType::DailyReview.as_str(), "readwise-daily-review");
+    assert_eq!(ReadwiseType::ReaderLater.as_str(), "readwise-reader-later");
 }
 
 #[test]
 fn test_readwise_config_new() {
     let config = ReadwiseConfig::new("test_token".to_string());
-    assert_eq!(config.access_token, "test_token");
+    assert_eq!(config.access_token.expose_secret(), "test_token");
     assert_eq!(config.base_url, "https://readwise.io/api/v2");
 }
+
+#[test]
+fn test_readwise_config_debug_redacts_token() {
+    let config = ReadwiseConfig::new("test_token".to_string());
+    assert!(!format!("{:?}", config).contains("test_token"));
+}
+
+#[test]
+fn test_retry_policy_backoff_is_exponential_and_capped() {
+    let policy = RetryPolicy {
+        max_attempts: 10,
+        max_wait: Duration::from_secs(5),
+        initial_backoff: Duration::from_secs(1),
+    };
+    assert_eq!(policy.backoff(1), Duration::from_secs(1));
+    assert_eq!(policy.backoff(2), Duration::from_secs(2));
+    assert_eq!(policy.backoff(3), Duration::from_secs(4));
+    assert_eq!(policy.backoff(4), Duration::from_secs(5));
+    assert_eq!(policy.backoff(40), Duration::from_secs(5));
+}
+
+#[test]
+fn test_readwise_error_rate_limited_message() {
+    let error = ReadwiseError::RateLimited {
+        retry_after: Some(Duration::from_secs(30)),
+    };
+    assert_eq!(
+        error.to_string(),
+        "Rate limit exceeded (429). Readwise asked to retry after 30s."
+    );
+}
+
+#[test]
+fn test_readwise_error_exit_codes() {
+    use clientele::SysexitsError::*;
+
+    let exit_code = |error: ReadwiseError| clientele::SysexitsError::from(&error);
+    assert_eq!(
+        exit_code(ReadwiseError::Unauthorized {
+            status: 401,
+            token_source: None
+        }),
+        EX_NOPERM
+    );
+    assert_eq!(
+        exit_code(ReadwiseError::RateLimited { retry_after: None }),
+        EX_TEMPFAIL
+    );
+    assert_eq!(
+        exit_code(ReadwiseError::Http {
+            status: 503,
+            body: String::new()
+        }),
+        EX_UNAVAILABLE
+    );
+    assert_eq!(
+        exit_code(ReadwiseError::Http {
+            status: 400,
+            body: String::new()
+        }),
+        EX_DATAERR
+    );
+}
+
+#[test]
+fn test_cli_error_exit_codes() {
+    use asimov_readwise_module::output::{CliError, ErrorFormat, write_error};
+    use clientele::SysexitsError::*;
+
+    let error = CliError::config("no Readwise access token found");
+    assert_eq!(error.kind(), "config");
+    assert_eq!(write_error(&error, &ErrorFormat::Json), EX_CONFIG);
+    assert_eq!(
+        write_error(&CliError::usage("missing INPUT-URL"), &ErrorFormat::Text),
+        EX_USAGE
+    );
+
+    let error = CliError::new(EX_CANTCREAT, "failed to write sync state");
+    assert_eq!(error.kind(), "io");
+    assert_eq!(write_error(&error, &ErrorFormat::Json), EX_CANTCREAT);
+}
+
+#[test]
+fn test_summarize_tags_counts_books_and_highlights() {
+    let books: Vec<ExportBook> = serde_json::from_value(serde_json::json!([
+        {
+            "user_book_id": 1,
+            "book_tags": [{ "id": 10, "name": "favorite" }],
+            "highlights": [
+                { "id": 100, "tags": [{ "id": 20, "name": "architecture" }] },
+                { "id": 101, "tags": [
+                    { "id": 20, "name": "architecture" },
+                    { "id": 10, "name": "favorite" }
+                ] }
+            ]
+        },
+        {
+            "user_book_id": 2,
+            "book_tags": [{ "id": 10, "name": "favorite" }],
+            "highlights": []
+        }
+    ]))
+    .unwrap();
+
+    let tags = summarize_tags(&books);
+    assert_eq!(tags.len(), 2);
+    assert_eq!(tags[0].name, "architecture");
+    assert_eq!(tags[0].highlight_count, 2);
+    assert_eq!(tags[0].book_count, 0);
+    assert_eq!(tags[1].name, "favorite");
+    assert_eq!(tags[1].highlight_count, 1);
+    assert_eq!(tags[1].book_count, 2);
+}
+
+#[test]
+fn test_collect_tagged_picks_highlights_and_books() {
+    let books: Vec<ExportBook> = serde_json::from_value(serde_json::json!([
+        {
+            "user_book_id": 1,
+            "book_tags": [{ "id": 10, "name": "favorite" }],
+            "highlights": [
+                { "id": 100, "book_id": 1, "tags": [{ "id": 20, "name": "architecture" }] },
+                { "id": 101, "book_id": 1, "tags": [] }
+            ]
+        },
+        {
+            "user_book_id": 2,
+            "book_tags": [{ "id": 20, "name": "architecture" }],
+            "highlights": [{ "id": 200, "book_id": 2 }]
+        }
+    ]))
+    .unwrap();
+
+    let tagged = collect_tagged(&books, "architecture");
+    assert_eq!(tagged.name, "architecture");
+    let highlight_ids: Vec<_> = tagged.highlights.iter().map(|h| h.id).collect();
+    assert_eq!(highlight_ids, vec![Some(100)]);
+    assert_eq!(tagged.highlights[0].book_id, Some(1));
+    let book_ids: Vec<_> = tagged.books.iter().map(|b| b.id).collect();
+    assert_eq!(book_ids, vec![Some(2)]);
+}
+
+#[test]
+fn test_stamp_account_rewrites_readwise_ids() {
+    let mut json_ld = serde_json::json!({
+        "@context": { "id": { "@id": "know:id" } },
+        "@id": "https://readwise.io/highlights",
+        "highlights": {
+            "items": [{ "@id": "https://readwise.io/open/123" }]
+        },
+        "documents": {
+            "items": [{ "@id": "https://read.readwise.io/read/abc" }]
+        }
+    });
+    stamp_account(&mut json_ld, "work team");
+
+    assert_eq!(json_ld["@context"]["id"]["@id"], "know:id");
+    assert_eq!(
+        json_ld["@id"],
+        "https://readwise.io/highlights?account=work+team"
+    );
+    assert_eq!(
+        json_ld["highlights"]["items"][0]["@id"],
+        "https://readwise.io/open/123?account=work+team"
+    );
+    assert_eq!(
+        json_ld["documents"]["items"][0]["@id"],
+        "https://read.readwise.io/read/abc?account=work+team"
+    );
+}
+
+#[test]
+fn test_config_file_profiles() {
+    let config: ConfigFile = serde_json::from_value(serde_json::json!({
+        "profiles": {
+            "default": { "token": "default-token" },
+            "work": { "token_file": "/run/secrets/readwise", "page_size": 500 },
+            "shared": { "account": "team" }
+        }
+    }))
+    .unwrap();
+
+    assert_eq!(config.account("default"), None);
+    assert_eq!(config.account("work").as_deref(), Some("work"));
+    assert_eq!(config.account("shared").as_deref(), Some("team"));
+    assert_eq!(config.require_profile("work").unwrap().page_size, Some(500));
+
+    let error = config.require_profile("home").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "unknown profile \"home\" (known: default, shared, work)"
+    );
+}
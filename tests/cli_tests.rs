@@ -0,0 +1,31 @@
+// This is free and unencumbered software released into the public domain.
+
+use asimov_readwise_module::cli::CommonOptions;
+use asimov_readwise_module::output::OutputFormat;
+use clap::{CommandFactory, Parser};
+
+#[derive(Parser)]
+struct Options {
+    #[command(flatten)]
+    cli: CommonOptions,
+}
+
+#[test]
+fn test_common_options_flatten() {
+    Options::command().debug_assert();
+
+    let options = Options::parse_from([
+        "asimov-readwise-importer",
+        "https://readwise.io/highlights",
+        "--limit",
+        "5",
+        "-o",
+        "jsonl",
+    ]);
+    assert_eq!(
+        options.cli.input_url.as_deref(),
+        Some("https://readwise.io/highlights")
+    );
+    assert_eq!(options.cli.limit, Some(5));
+    assert!(matches!(options.cli.output, Some(OutputFormat::Jsonl)));
+}
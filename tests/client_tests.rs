@@ -0,0 +1,634 @@
+// This is free and unencumbered software released into the public domain.
+
+use asimov_readwise_module::api::{
+    ReadwiseError,
+    builder::ReadwiseClientBuilder,
+    filter::{BookFilter, DocumentFilter, HighlightFilter, ReaderLocation},
+    limiter::{Budget, RateLimits},
+    readwise::{
+        DEFAULT_MAX_BODY_SIZE, RateLimitPolicy, ReadwiseClient, ReadwiseConfig, RetryPolicy,
+    },
+    token::TokenSource,
+    transport::{FixtureTransport, HttpRequest, HttpResponse, Transport, TransportError},
+};
+use asimov_readwise_module::config::Profile;
+use std::{sync::Arc, time::Duration};
+
+const BASE_URL: &str = "http://readwise.test/api/v2";
+
+fn client(transport: Arc<FixtureTransport>) -> ReadwiseClient {
+    let mut config = ReadwiseConfig::new("test_token".to_string());
+    config.base_url = BASE_URL.to_string();
+    config.rate_limits = RateLimits::unlimited();
+    config.retry = RetryPolicy {
+        max_attempts: 3,
+        max_wait: Duration::from_secs(1),
+        initial_backoff: Duration::ZERO,
+    };
+    ReadwiseClient::with_transport(config, transport).unwrap()
+}
+
+fn highlights_page(ids: &[u64], next: Option<&str>) -> HttpResponse {
+    let results: Vec<_> = ids
+        .iter()
+        .map(|id| serde_json::json!({ "id": id, "text": format!("highlight {}", id) }))
+        .collect();
+    let body =
+        serde_json::json!({ "count": 3, "next": next, "previous": null, "results": results });
+    HttpResponse::new(200, body.to_string())
+}
+
+#[test]
+fn test_fetch_all_highlights_follows_next_links() {
+    let page_2 = format!("{}/highlights/?page_size=2&page=2", BASE_URL);
+    let transport = Arc::new(
+        FixtureTransport::new()
+            .with_response(
+                format!("{}/highlights/?page_size=2", BASE_URL),
+                highlights_page(&[1, 2], Some(&page_2)),
+            )
+            .with_response(page_2, highlights_page(&[3], None)),
+    );
+
+    let highlights = client(transport.clone())
+        .fetch_all_highlights(Some(2), None, &HighlightFilter::default())
+        .unwrap();
+
+    let ids: Vec<_> = highlights.results.unwrap().iter().map(|h| h.id).collect();
+    assert_eq!(ids, vec![Some(1), Some(2), Some(3)]);
+    assert_eq!(highlights.next, None);
+    assert_eq!(transport.requests().len(), 2);
+    assert_eq!(
+        transport.requests()[0].headers,
+        vec![("Authorization".to_string(), "Token test_token".to_string())]
+    );
+}
+
+#[test]
+fn test_fetch_all_highlights_stops_at_limit() {
+    let page_2 = format!("{}/highlights/?page_size=2&page=2", BASE_URL);
+    let transport = Arc::new(FixtureTransport::new().with_response(
+        format!("{}/highlights/?page_size=2", BASE_URL),
+        highlights_page(&[1, 2], Some(&page_2)),
+    ));
+
+    let highlights = client(transport.clone())
+        .fetch_all_highlights(None, Some(2), &HighlightFilter::default())
+        .unwrap();
+
+    assert_eq!(highlights.results.unwrap().len(), 2);
+    assert_eq!(highlights.next, Some(page_2));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[test]
+fn test_fetch_all_highlights_drops_next_link_of_truncated_page() {
+    let page_2 = format!("{}/highlights/?page_size=2&page=2", BASE_URL);
+    let transport = Arc::new(FixtureTransport::new().with_response(
+        format!("{}/highlights/?page_size=2", BASE_URL),
+        highlights_page(&[1, 2], Some(&page_2)),
+    ));
+
+    let highlights = client(transport)
+        .fetch_all_highlights(Some(2), Some(1), &HighlightFilter::default())
+        .unwrap();
+
+    assert_eq!(highlights.results.unwrap().len(), 1);
+    assert_eq!(highlights.next, None);
+}
+
+#[test]
+fn test_fetch_all_documents_drops_cursor_of_truncated_page() {
+    let transport = Arc::new(FixtureTransport::new().with_response(
+        "http://readwise.test/api/v3/list/",
+        HttpResponse::new(
+            200,
+            r#"{"count": 3, "nextPageCursor": "abc", "results": [{"id": "d1"}, {"id": "d2"}]}"#,
+        ),
+    ));
+
+    let documents = client(transport)
+        .fetch_all_documents(&DocumentFilter::default(), Some(1))
+        .unwrap();
+
+    assert_eq!(documents.results.unwrap().len(), 1);
+    assert!(documents.next_page_cursor.is_none());
+}
+
+#[test]
+fn test_fetch_book_detail_nests_the_books_highlights() {
+    let transport = Arc::new(
+        FixtureTransport::new()
+            .with_response(
+                format!("{}/books/7/", BASE_URL),
+                HttpResponse::new(200, r#"{"id": 7, "title": "Sample Book"}"#),
+            )
+            .with_response(
+                format!("{}/highlights/?page_size=1000&book_id=7", BASE_URL),
+                highlights_page(&[1, 2], None),
+            ),
+    );
+
+    let book = client(transport.clone())
+        .fetch_book_detail(7, None, None, &HighlightFilter::default())
+        .unwrap();
+
+    assert_eq!(book.book.title.as_deref(), Some("Sample Book"));
+    let ids: Vec<_> = book.highlights.iter().map(|h| h.id).collect();
+    assert_eq!(ids, vec![Some(1), Some(2)]);
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[test]
+fn test_fetch_highlight_detail_resolves_its_book() {
+    let transport = Arc::new(
+        FixtureTransport::new()
+            .with_response(
+                format!("{}/highlights/3/", BASE_URL),
+                HttpResponse::new(200, r#"{"id": 3, "text": "highlight 3", "book_id": 7}"#),
+            )
+            .with_response(
+                format!("{}/books/7/", BASE_URL),
+                HttpResponse::new(200, r#"{"id": 7, "title": "Sample Book"}"#),
+            ),
+    );
+
+    let highlight = client(transport).fetch_highlight_detail(3).unwrap();
+
+    assert_eq!(highlight.highlight.text.as_deref(), Some("highlight 3"));
+    assert_eq!(
+        highlight.book.unwrap().title.as_deref(),
+        Some("Sample Book")
+    );
+}
+
+#[test]
+fn test_fetch_daily_review_is_dated() {
+    let body = serde_json::json!({
+        "review_id": 42,
+        "review_url": "https://readwise.io/reviews/42",
+        "review_completed": false,
+        "highlights": [{ "id": 1, "text": "highlight 1", "title": "Sample Book" }]
+    });
+    let transport = Arc::new(FixtureTransport::new().with_response(
+        format!("{}/review/", BASE_URL),
+        HttpResponse::new(200, body.to_string()),
+    ));
+
+    let review = client(transport).fetch_daily_review().unwrap();
+
+    assert_eq!(review.review_id, Some(42));
+    assert_eq!(review.highlights.unwrap()[0].id, Some(1));
+    let date = review.date.unwrap();
+    assert!(chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d").is_ok());
+}
+
+#[test]
+fn test_fetch_all_documents_follows_reader_cursor() {
+    let first = "http://readwise.test/api/v3/list/?location=later";
+    let second = "http://readwise.test/api/v3/list/?location=later&pageCursor=abc";
+    let transport = Arc::new(
+        FixtureTransport::new()
+            .with_response(
+                first,
+                HttpResponse::new(
+                    200,
+                    r#"{"count": 2, "nextPageCursor": "abc", "results": [{"id": "d1"}]}"#,
+                ),
+            )
+            .with_response(
+                second,
+                HttpResponse::new(
+                    200,
+                    r#"{"count": 2, "nextPageCursor": null, "results": [{"id": "d2", "reading_progress": 0.5}]}"#,
+                ),
+            ),
+    );
+
+    let filter = DocumentFilter {
+        location: Some(ReaderLocation::Later),
+        ..Default::default()
+    };
+    let documents = client(transport.clone())
+        .fetch_all_documents(&filter, None)
+        .unwrap();
+
+    let results = documents.results.unwrap();
+    let ids: Vec<_> = results.iter().map(|d| d.id.as_deref()).collect();
+    assert_eq!(ids, vec![Some("d1"), Some("d2")]);
+    assert_eq!(results[1].reading_progress, Some(0.5));
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[test]
+fn test_reader_base_url_can_be_set() {
+    let config = ReadwiseClient::builder()
+        .access_token("test_token")
+        .base_url("http://localhost:8080/mock")
+        .reader_base_url("https://reader.example.com/api/v3/")
+        .build_config()
+        .unwrap();
+    assert_eq!(
+        config.reader_endpoint_url("/list/"),
+        "https://reader.example.com/api/v3/list/"
+    );
+}
+
+#[test]
+fn test_rate_limited_request_is_retried() {
+    let url = format!("{}/books/?page=1", BASE_URL);
+    let transport = Arc::new(
+        FixtureTransport::new()
+            .with_response(
+                &url,
+                HttpResponse::new(429, "").with_header("Retry-After", "0"),
+            )
+            .with_response(&url, HttpResponse::new(200, r#"{"results": []}"#)),
+    );
+
+    let books = client(transport.clone())
+        .fetch_booklist(None, Some(1), &BookFilter::default())
+        .unwrap();
+
+    assert!(books.results.unwrap().is_empty());
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[test]
+fn test_rate_limit_beyond_max_wait_is_not_retried() {
+    let transport = Arc::new(FixtureTransport::new().with_response(
+        format!("{}/books/?page=1", BASE_URL),
+        HttpResponse::new(429, "").with_header("Retry-After", "60"),
+    ));
+
+    let error = client(transport.clone())
+        .fetch_booklist(None, Some(1), &BookFilter::default())
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        ReadwiseError::RateLimited {
+            retry_after: Some(wait)
+        } if wait == Duration::from_secs(60)
+    ));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+/// A transport failing with the given errors before delegating to a
+/// fixture transport.
+struct FailingTransport {
+    errors: std::sync::Mutex<Vec<TransportError>>,
+    inner: Arc<FixtureTransport>,
+}
+
+impl Transport for FailingTransport {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, ReadwiseError> {
+        match self.errors.lock().unwrap().pop() {
+            Some(error) => Err(error.into()),
+            None => self.inner.send(request),
+        }
+    }
+}
+
+#[test]
+fn test_transient_transport_errors_are_retried() {
+    let url = format!("{}/books/?page=1", BASE_URL);
+    let fixtures = Arc::new(
+        FixtureTransport::new().with_response(&url, HttpResponse::new(200, r#"{"results": []}"#)),
+    );
+    let send = |error: TransportError| {
+        let mut config = ReadwiseConfig::new("test_token".to_string());
+        config.base_url = BASE_URL.to_string();
+        config.rate_limits = RateLimits::unlimited();
+        config.retry.initial_backoff = Duration::ZERO;
+        let transport = FailingTransport {
+            errors: std::sync::Mutex::new(vec![error]),
+            inner: fixtures.clone(),
+        };
+        ReadwiseClient::with_transport(config, transport)
+            .unwrap()
+            .fetch_booklist(None, Some(1), &BookFilter::default())
+    };
+
+    assert!(send(TransportError::transient("connection reset")).is_ok());
+
+    let error = send(TransportError::permanent("certificate not trusted")).unwrap_err();
+    assert!(matches!(error, ReadwiseError::Transport(_)));
+    assert!(!error.is_retryable());
+    assert_eq!(fixtures.requests().len(), 1);
+}
+
+#[test]
+fn test_unauthorized_response() {
+    let transport = Arc::new(FixtureTransport::new().with_response(
+        format!("{}/books/?page=1", BASE_URL),
+        HttpResponse::new(401, r#"{"detail":"Invalid token."}"#),
+    ));
+
+    let error = client(transport)
+        .fetch_booklist(None, Some(1), &BookFilter::default())
+        .unwrap_err();
+    assert!(matches!(error, ReadwiseError::Unauthorized { .. }));
+}
+
+#[test]
+fn test_forbidden_response_names_the_token_source() {
+    let transport = FixtureTransport::new().with_response(
+        format!("{}/books/?page=1", BASE_URL),
+        HttpResponse::new(403, r#"{"detail":"Forbidden."}"#),
+    );
+    let mut client = ReadwiseClient::builder()
+        .resolved_token(
+            "test_token".to_string().into(),
+            &TokenSource::Command("pass readwise".to_string()),
+        )
+        .base_url(BASE_URL)
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    let error = client
+        .fetch_booklist(None, Some(1), &BookFilter::default())
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        ReadwiseError::Unauthorized { status: 403, .. }
+    ));
+    assert_eq!(
+        error.to_string(),
+        "Readwise rejected the access token from `pass readwise` (HTTP 403)"
+    );
+}
+
+#[test]
+fn test_malformed_response_is_a_decode_error() {
+    let transport = Arc::new(FixtureTransport::new().with_response(
+        format!("{}/books/?page=1", BASE_URL),
+        HttpResponse::new(200, "<html>"),
+    ));
+
+    let error = client(transport)
+        .fetch_booklist(None, Some(1), &BookFilter::default())
+        .unwrap_err();
+    assert!(matches!(error, ReadwiseError::Decode { .. }));
+}
+
+#[test]
+fn test_record_and_replay_responses() {
+    use asimov_readwise_module::api::recording::{RecordingTransport, ReplayTransport};
+
+    let dir = std::env::temp_dir().join("asimov-readwise-recording-test");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let transport = Arc::new(FixtureTransport::new().with_response(
+        format!("{}/books/?page=1", BASE_URL),
+        HttpResponse::new(200, r#"{"count": 1, "results": [{"id": 7}]}"#),
+    ));
+    let mut config = ReadwiseConfig::new("secret_token".to_string());
+    config.base_url = BASE_URL.to_string();
+    let mut recorder =
+        ReadwiseClient::with_transport(config, RecordingTransport::new(transport, &dir)).unwrap();
+    recorder
+        .fetch_booklist(None, Some(1), &BookFilter::default())
+        .unwrap();
+
+    for entry in std::fs::read_dir(&dir).unwrap() {
+        let fixture = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+        assert!(!fixture.contains("secret_token"));
+    }
+
+    let mut config = ReadwiseConfig::new(String::new());
+    config.base_url = "http://localhost:8080/api/v2".to_string();
+    config.rate_limits = RateLimits::unlimited();
+    let mut replayer = ReadwiseClient::with_transport(config, ReplayTransport::new(&dir)).unwrap();
+    let books = replayer
+        .fetch_booklist(None, Some(1), &BookFilter::default())
+        .unwrap();
+    assert_eq!(books.results.unwrap()[0].id, Some(7));
+
+    let error = replayer
+        .fetch_booklist(None, Some(2), &BookFilter::default())
+        .unwrap_err();
+    assert!(matches!(error, ReadwiseError::Fixture { .. }));
+    assert!(!error.is_retryable());
+    assert_eq!(
+        clientele::SysexitsError::from(&error),
+        clientele::SysexitsError::EX_NOINPUT
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Serves `body` as a `200 OK` to every connection on a local port, and
+/// returns the base URL to reach it.
+fn serve(body: String) -> String {
+    use std::io::{BufRead, BufReader, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                line.clear();
+            }
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = stream.write_all(head.as_bytes());
+            let _ = stream.write_all(body.as_bytes());
+        }
+    });
+    format!("http://{}/api/v2", address)
+}
+
+#[test]
+fn test_response_bodies_over_10_mb_are_read() {
+    let text = "x".repeat(11 * 1024 * 1024);
+    let body = serde_json::json!({
+        "count": 1,
+        "nextPageCursor": null,
+        "results": [{ "user_book_id": 1, "highlights": [{ "id": 1, "text": text }] }],
+    });
+    let base_url = serve(body.to_string());
+
+    let build = |max_body_size| {
+        ReadwiseClient::builder()
+            .access_token("test_token")
+            .base_url(&base_url)
+            .rate_limits(RateLimits::unlimited())
+            .max_attempts(1)
+            .max_body_size(max_body_size)
+            .build()
+            .unwrap()
+    };
+
+    let export = build(DEFAULT_MAX_BODY_SIZE)
+        .fetch_export(None, None)
+        .unwrap();
+    let highlights = export.results.unwrap()[0].highlights.clone().unwrap();
+    assert_eq!(highlights[0].text.as_ref().unwrap().len(), text.len());
+
+    let error = build(1024 * 1024).fetch_export(None, None).unwrap_err();
+    assert!(matches!(error, ReadwiseError::Transport(_)), "{}", error);
+    assert!(!error.is_retryable());
+}
+
+#[test]
+fn test_invalid_proxy_is_a_config_error() {
+    let mut config = ReadwiseConfig::new("test_token".to_string());
+    config.proxy = Some("not a proxy url".to_string());
+    assert!(matches!(
+        ReadwiseClient::new(config),
+        Err(ReadwiseError::Config(_))
+    ));
+}
+
+#[test]
+fn test_missing_ca_bundle_is_a_config_error() {
+    let mut config = ReadwiseConfig::new("test_token".to_string());
+    config.ca_bundle = Some("/nonexistent/ca-bundle.pem".into());
+    assert!(matches!(
+        ReadwiseClient::new(config),
+        Err(ReadwiseError::Config(_))
+    ));
+}
+
+#[test]
+fn test_builder_validates_settings() {
+    let error = |builder: ReadwiseClientBuilder| builder.build().err().unwrap();
+
+    assert!(matches!(
+        error(ReadwiseClient::builder()),
+        ReadwiseError::Config(_)
+    ));
+    assert!(matches!(
+        error(ReadwiseClient::builder().access_token("t").page_size(0)),
+        ReadwiseError::Config(_)
+    ));
+    assert!(matches!(
+        error(
+            ReadwiseClient::builder()
+                .access_token("t")
+                .base_url("readwise.io")
+        ),
+        ReadwiseError::Config(_)
+    ));
+    assert!(matches!(
+        error(ReadwiseClient::builder().access_token("t").max_attempts(0)),
+        ReadwiseError::Config(_)
+    ));
+    assert!(matches!(
+        error(ReadwiseClient::builder().token_source(TokenSource::Env(
+            "ASIMOV_READWISE_TEST_UNSET_VARIABLE".to_string()
+        ))),
+        ReadwiseError::Config(_)
+    ));
+}
+
+#[test]
+fn test_builder_applies_default_page_size_and_rate_limit_policy() {
+    let url = format!("{}/books/?page_size=50&page=1", BASE_URL);
+    let transport = Arc::new(FixtureTransport::new().with_response(
+        &url,
+        HttpResponse::new(429, "").with_header("Retry-After", "0"),
+    ));
+
+    let mut client = ReadwiseClient::builder()
+        .base_url(format!("{}/", BASE_URL))
+        .access_token("test_token")
+        .page_size(50)
+        .rate_limit_policy(RateLimitPolicy::Fail)
+        .transport(transport.clone())
+        .build()
+        .unwrap();
+
+    let error = client
+        .fetch_booklist(None, Some(1), &BookFilter::default())
+        .unwrap_err();
+    assert!(matches!(error, ReadwiseError::RateLimited { .. }));
+    assert_eq!(transport.requests().len(), 1);
+    assert_eq!(transport.requests()[0].url, url);
+}
+
+#[test]
+fn test_builder_applies_profile_settings() {
+    let profile: Profile = serde_json::from_value(serde_json::json!({
+        "base_url": "https://readwise.example.com/api/v2/",
+        "page_size": 250,
+        "max_attempts": 2
+    }))
+    .unwrap();
+
+    let config = ReadwiseClient::builder()
+        .access_token("test_token")
+        .profile(&profile)
+        .max_attempts(3)
+        .build_config()
+        .unwrap();
+
+    assert_eq!(config.base_url, "https://readwise.example.com/api/v2");
+    assert_eq!(config.page_size, Some(250));
+    assert_eq!(config.retry.max_attempts, 3);
+}
+
+#[test]
+fn test_verify_token() {
+    let url = format!("{}/auth/", BASE_URL);
+    let transport = FixtureTransport::new().with_response(
+        &url,
+        HttpResponse::new(204, "").with_header("Date", "Sun, 18 Oct 2026 10:00:00 GMT"),
+    );
+    let client = client(Arc::new(transport));
+
+    let status = client.verify_token().unwrap();
+    assert_eq!(
+        status.server_date.as_deref(),
+        Some("Sun, 18 Oct 2026 10:00:00 GMT")
+    );
+}
+
+#[test]
+fn test_verify_token_rejected() {
+    let url = format!("{}/auth/", BASE_URL);
+    let transport = FixtureTransport::new().with_response(&url, HttpResponse::new(401, ""));
+    let client = client(Arc::new(transport));
+
+    assert!(matches!(
+        client.verify_token(),
+        Err(ReadwiseError::Unauthorized { .. })
+    ));
+}
+
+#[test]
+fn test_requests_are_paced_by_endpoint_budget() {
+    let first = format!("{}/highlights/?page_size=1", BASE_URL);
+    let second = format!("{}/highlights/?page=2&page_size=1", BASE_URL);
+    let transport = FixtureTransport::new()
+        .with_response(&first, highlights_page(&[1], Some(&second)))
+        .with_response(&second, highlights_page(&[2], None));
+
+    let budget = Budget {
+        requests: 10,
+        per: Duration::from_secs(1),
+        burst: 1,
+    };
+    let mut client = ReadwiseClient::builder()
+        .base_url(BASE_URL)
+        .access_token("test_token")
+        .rate_limits(RateLimits::unlimited())
+        .rate_limit("/highlights/", budget)
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    let started_at = std::time::Instant::now();
+    let highlights = client
+        .fetch_all_highlights(Some(1), None, &HighlightFilter::default())
+        .unwrap();
+    assert_eq!(highlights.results.unwrap().len(), 2);
+    assert!(started_at.elapsed() >= Duration::from_millis(90));
+}
@@ -0,0 +1,82 @@
+// This is free and unencumbered software released into the public domain.
+
+use asimov_readwise_module::{
+    api::{
+        readwise::ReadwiseClient,
+        token::TokenSource,
+        transport::{FixtureTransport, HttpResponse},
+    },
+    doctor::{self, Status},
+};
+
+const BASE_URL: &str = "http://readwise.test/api/v2";
+
+fn status(report: &doctor::Report, name: &str) -> Status {
+    report.check(name).unwrap().status
+}
+
+#[test]
+fn test_doctor_reports_accepted_token() {
+    let date = chrono::Utc::now().to_rfc2822();
+    let transport = FixtureTransport::new().with_response(
+        format!("{}/auth/", BASE_URL),
+        HttpResponse::new(204, "").with_header("Date", date),
+    );
+    let builder = ReadwiseClient::builder()
+        .base_url(BASE_URL)
+        .transport(transport);
+
+    let report = doctor::run(builder, &[TokenSource::Value("test_token".into())]);
+    assert!(report.ok);
+    assert_eq!(status(&report, "token"), Status::Ok);
+    assert_eq!(status(&report, "reachability"), Status::Ok);
+    assert_eq!(status(&report, "auth"), Status::Ok);
+    assert_eq!(status(&report, "clock"), Status::Ok);
+}
+
+#[test]
+fn test_doctor_reports_clock_skew() {
+    let date = (chrono::Utc::now() - chrono::Duration::minutes(10)).to_rfc2822();
+    let transport = FixtureTransport::new().with_response(
+        format!("{}/auth/", BASE_URL),
+        HttpResponse::new(204, "").with_header("Date", date),
+    );
+    let builder = ReadwiseClient::builder()
+        .base_url(BASE_URL)
+        .transport(transport);
+
+    let report = doctor::run(builder, &[TokenSource::Value("test_token".into())]);
+    assert!(report.ok);
+    assert_eq!(status(&report, "clock"), Status::Warn);
+    assert!(report.check("clock").unwrap().message.contains("ahead of"));
+}
+
+#[test]
+fn test_doctor_reports_rejected_token() {
+    let transport = FixtureTransport::new()
+        .with_response(format!("{}/auth/", BASE_URL), HttpResponse::new(401, ""));
+    let builder = ReadwiseClient::builder()
+        .base_url(BASE_URL)
+        .transport(transport);
+
+    let report = doctor::run(builder, &[TokenSource::Value("bad_token".into())]);
+    assert!(!report.ok);
+    assert_eq!(status(&report, "reachability"), Status::Ok);
+    assert_eq!(status(&report, "auth"), Status::Fail);
+    assert_eq!(report.exit_code(), clientele::SysexitsError::EX_NOPERM);
+}
+
+#[test]
+fn test_doctor_reports_missing_token() {
+    let sources = [TokenSource::Env(
+        "ASIMOV_READWISE_TEST_UNSET_VARIABLE".to_string(),
+    )];
+    let report = doctor::run(ReadwiseClient::builder(), &sources);
+    assert!(!report.ok);
+    assert_eq!(status(&report, "token"), Status::Fail);
+    assert!(report.check("auth").is_none());
+    assert_eq!(report.exit_code(), clientele::SysexitsError::EX_CONFIG);
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["checks"][1]["status"], "fail");
+}
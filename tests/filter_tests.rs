@@ -0,0 +1,173 @@
+// This is free and unencumbered software released into the public domain.
+
+use asimov_readwise_module::api::{
+    filter::{
+        BookCategory, BookFilter, DocumentCategory, DocumentFilter, HighlightFilter, ReaderLocation,
+    },
+    limiter::RateLimits,
+    readwise::ReadwiseClient,
+    transport::{FixtureTransport, HttpResponse},
+};
+use std::sync::Arc;
+
+const BASE_URL: &str = "http://readwise.test/api/v2";
+
+fn client(transport: Arc<FixtureTransport>) -> ReadwiseClient {
+    ReadwiseClient::builder()
+        .base_url(BASE_URL)
+        .access_token("test_token")
+        .rate_limits(RateLimits::unlimited())
+        .transport(transport)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_book_category_round_trip() {
+    for category in BookCategory::ALL {
+        assert_eq!(category.as_str().parse::<BookCategory>(), Ok(category));
+    }
+    assert!("videos".parse::<BookCategory>().is_err());
+}
+
+#[test]
+fn test_highlight_filter_query_params() {
+    let filter = HighlightFilter {
+        book_id: Some(42),
+        updated_after: Some("2025-01-01T00:00:00Z".to_string()),
+        highlighted_before: Some("2025-02-01T00:00:00Z".to_string()),
+        ..Default::default()
+    };
+    assert!(filter.is_partial());
+    assert_eq!(
+        filter.query_params(),
+        vec![
+            ("book_id", "42".to_string()),
+            ("updated__gt", "2025-01-01T00:00:00Z".to_string()),
+            ("highlighted_at__lt", "2025-02-01T00:00:00Z".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_updated_after_alone_is_not_partial() {
+    let filter = BookFilter {
+        updated_after: Some("2025-01-01T00:00:00Z".to_string()),
+        ..Default::default()
+    };
+    assert!(!filter.is_partial());
+}
+
+#[test]
+fn test_fetch_highlights_of_one_book() {
+    let url = format!("{}/highlights/?page_size=10&book_id=42", BASE_URL);
+    let transport = Arc::new(FixtureTransport::new().with_response(
+        &url,
+        HttpResponse::new(
+            200,
+            r#"{"count": 1, "results": [{"id": 1, "book_id": 42}]}"#,
+        ),
+    ));
+
+    let filter = HighlightFilter {
+        book_id: Some(42),
+        ..Default::default()
+    };
+    let highlights = client(transport.clone())
+        .fetch_all_highlights(Some(10), None, &filter)
+        .unwrap();
+    assert_eq!(highlights.results.unwrap().len(), 1);
+    assert_eq!(transport.requests()[0].url, url);
+}
+
+#[test]
+fn test_fetch_books_by_category_and_source() {
+    let url = format!(
+        "{}/books/?page=1&category=articles&source=reader&last_highlight_at__gt=2025-01-01T00%3A00%3A00Z",
+        BASE_URL
+    );
+    let transport = Arc::new(FixtureTransport::new().with_response(
+        &url,
+        HttpResponse::new(200, r#"{"count": 0, "results": []}"#),
+    ));
+
+    let filter = BookFilter {
+        category: Some(BookCategory::Articles),
+        source: Some("reader".to_string()),
+        last_highlight_after: Some("2025-01-01T00:00:00Z".to_string()),
+        ..Default::default()
+    };
+    client(transport.clone())
+        .fetch_booklist(None, Some(1), &filter)
+        .unwrap();
+    assert_eq!(transport.requests()[0].url, url);
+}
+
+#[test]
+fn test_highlight_filter_from_query() {
+    let filter = HighlightFilter::from_query("book_id=123&updated_after=2025-01-01").unwrap();
+    assert_eq!(
+        filter,
+        HighlightFilter {
+            book_id: Some(123),
+            updated_after: Some("2025-01-01T00:00:00Z".to_string()),
+            ..Default::default()
+        }
+    );
+
+    let filter =
+        HighlightFilter::from_query("highlighted_at__lt=2025-02-01T01:00:00%2B01:00").unwrap();
+    assert_eq!(
+        filter.highlighted_before.as_deref(),
+        Some("2025-02-01T00:00:00Z")
+    );
+}
+
+#[test]
+fn test_book_filter_from_query() {
+    let filter = BookFilter::from_query("category=articles&source=reader").unwrap();
+    assert_eq!(filter.category, Some(BookCategory::Articles));
+    assert_eq!(filter.source.as_deref(), Some("reader"));
+    assert_eq!(BookFilter::from_query("").unwrap(), BookFilter::default());
+}
+
+#[test]
+fn test_from_query_rejects_unknown_parameters_and_values() {
+    assert!(HighlightFilter::from_query("category=articles").is_err());
+    assert!(HighlightFilter::from_query("book_id=abc").is_err());
+    assert!(BookFilter::from_query("category=videos").is_err());
+    assert!(BookFilter::from_query("updated_after=yesterday").is_err());
+}
+
+#[test]
+fn test_filter_flags_override_query() {
+    let flags = BookFilter {
+        category: Some(BookCategory::Podcasts),
+        ..Default::default()
+    };
+    let query = BookFilter::from_query("category=articles&source=reader").unwrap();
+    let filter = flags.or(query);
+    assert_eq!(filter.category, Some(BookCategory::Podcasts));
+    assert_eq!(filter.source.as_deref(), Some("reader"));
+}
+
+#[test]
+fn test_document_filter_query_params() {
+    let filter = DocumentFilter::from_query("category=pdf&updated_after=2025-01-01").unwrap();
+    assert_eq!(filter.category, Some(DocumentCategory::Pdf));
+    assert!(filter.is_partial());
+
+    let filter = DocumentFilter {
+        location: Some(ReaderLocation::Later),
+        ..filter
+    };
+    assert_eq!(
+        filter.query_params(),
+        vec![
+            ("location", "later".to_string()),
+            ("category", "pdf".to_string()),
+            ("updatedAfter", "2025-01-01T00:00:00Z".to_string()),
+        ]
+    );
+    assert!(DocumentFilter::from_query("location=archive").is_err());
+}
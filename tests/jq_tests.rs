@@ -1,6 +1,8 @@
 // This is free and unencumbered software released into the public domain.
 
-use asimov_readwise_module::jq::{books, readwise, tags};
+use asimov_readwise_module::jq::{
+    book, books, export, highlight, reader, readwise, review, tag, tags,
+};
 use serde_json::json;
 
 #[test]
@@ -18,6 +20,11 @@ fn test_tags_jq_compilation() {
     let _filter = tags();
 }
 
+#[test]
+fn test_export_jq_compilation() {
+    let _filter = export();
+}
+
 #[test]
 fn test_readwise_jq_with_sample_data() {
     let filter = readwise();
@@ -58,6 +65,99 @@ fn test_books_jq_with_sample_data() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_book_jq_nests_highlights() {
+    let filter = book();
+    let sample_data = json!({
+        "id": 456,
+        "title": "Sample Book",
+        "author": "Sample Author",
+        "highlights": [
+            { "id": 123, "text": "Sample highlight text" }
+        ]
+    });
+
+    let result = filter.filter_json(sample_data).unwrap();
+    assert_eq!(result["@id"], "https://readwise.io/bookreview/456");
+    assert_eq!(result["@type"], "know:Book");
+    assert_eq!(result["highlights"]["count"], 1);
+    assert_eq!(
+        result["highlights"]["items"][0]["@id"],
+        "https://readwise.io/open/123"
+    );
+}
+
+#[test]
+fn test_highlight_jq_links_its_book() {
+    let filter = highlight();
+    let sample_data = json!({
+        "id": 123,
+        "text": "Sample highlight text",
+        "book_id": 456,
+        "book": { "id": 456, "title": "Sample Book" }
+    });
+
+    let result = filter.filter_json(sample_data).unwrap();
+    assert_eq!(result["@id"], "https://readwise.io/open/123");
+    assert_eq!(result["book"]["@id"], "https://readwise.io/bookreview/456");
+    assert_eq!(result["book"]["title"], "Sample Book");
+}
+
+#[test]
+fn test_books_jq_types_books_by_category() {
+    let result = books()
+        .filter_json(json!({
+            "results": [
+                { "id": 1, "category": "books" },
+                { "id": 2, "category": "articles" },
+                { "id": 3, "category": "tweets" },
+                { "id": 4, "category": "podcasts" },
+                { "id": 5, "category": "supplementals" },
+                { "id": 6 }
+            ]
+        }))
+        .unwrap();
+
+    let types: Vec<_> = result["books"]["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["@type"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            "know:Book",
+            "know:Article",
+            "know:SocialMediaPosting",
+            "know:PodcastEpisode",
+            "know:Book",
+            "know:Book"
+        ]
+    );
+}
+
+#[test]
+fn test_review_jq_is_a_dated_collection() {
+    let result = review()
+        .filter_json(json!({
+            "review_id": 42,
+            "review_completed": true,
+            "date": "2026-10-18",
+            "highlights": [{ "id": 123, "text": "Sample highlight text" }]
+        }))
+        .unwrap();
+
+    assert_eq!(result["@id"], "https://readwise.io/reviews/42");
+    assert_eq!(result["date"], "2026-10-18");
+    assert_eq!(result["completed"], true);
+    assert_eq!(result["highlights"]["count"], 1);
+    assert_eq!(
+        result["highlights"]["items"][0]["@id"],
+        "https://readwise.io/open/123"
+    );
+}
+
 #[test]
 fn test_tags_jq_with_sample_data() {
     let filter = tags();
@@ -85,3 +185,101 @@ fn test_tags_jq_with_fallback_data() {
     let result = filter.filter_json(sample_data);
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_export_jq_with_sample_data() {
+    let filter = export();
+    let sample_data = json!({
+        "count": 1,
+        "nextPageCursor": null,
+        "results": [
+            {
+                "user_book_id": 456,
+                "title": "Sample Book",
+                "author": "Sample Author",
+                "category": "books",
+                "book_tags": [{ "id": 1, "name": "favorite" }],
+                "highlights": [
+                    {
+                        "id": 123,
+                        "text": "Sample highlight text",
+                        "location": 42,
+                        "location_type": "page",
+                        "highlighted_at": "2024-01-01T00:00:00Z",
+                        "updated_at": "2024-01-01T00:00:00Z",
+                        "tags": [{ "id": 2, "name": "architecture" }]
+                    }
+                ]
+            }
+        ]
+    });
+
+    let result = filter.filter_json(sample_data).unwrap();
+    let book = &result["books"]["items"][0];
+    assert_eq!(result["books"]["count"], 1);
+    assert_eq!(book["tags"][0]["name"], "favorite");
+    assert_eq!(book["highlights"][0]["id"], 123);
+    assert_eq!(book["highlights"][0]["tags"][0]["name"], "architecture");
+    assert_eq!(book["@id"], "https://readwise.io/bookreview/456");
+    assert_eq!(book["highlights"][0]["@id"], "https://readwise.io/open/123");
+}
+
+#[test]
+fn test_tags_jq_escapes_tag_ids() {
+    let result = tags()
+        .filter_json(json!([{ "id": 1, "name": "to read" }]))
+        .unwrap();
+    assert_eq!(
+        result["tags"]["items"][0]["@id"],
+        "https://readwise.io/tags/to%20read"
+    );
+}
+
+#[test]
+fn test_tag_jq_combines_highlights_and_books() {
+    let highlights = readwise()
+        .filter_json(json!({ "results": [{ "id": 123, "text": "Sample highlight text" }] }))
+        .unwrap();
+    let books = books()
+        .filter_json(json!({ "results": [{ "id": 456, "title": "Sample Book" }] }))
+        .unwrap();
+
+    let result = tag()
+        .filter_json(json!({ "name": "to read", "highlights": highlights, "books": books }))
+        .unwrap();
+    assert_eq!(result["@id"], "https://readwise.io/tags/to%20read");
+    assert_eq!(result["@type"], "know:Tag");
+    assert_eq!(
+        result["highlights"]["items"][0]["@id"],
+        "https://readwise.io/open/123"
+    );
+    assert_eq!(
+        result["books"]["items"][0]["@id"],
+        "https://readwise.io/bookreview/456"
+    );
+    assert_eq!(result["@context"]["id"]["@type"], "xsd:integer");
+}
+
+#[test]
+fn test_reader_jq_maps_documents() {
+    let result = reader()
+        .filter_json(json!({
+            "count": 1,
+            "results": [{
+                "id": "01abc",
+                "title": "Sample Article",
+                "category": "article",
+                "location": "later",
+                "reading_progress": 0.25,
+                "tags": { "ai": { "name": "AI" } }
+            }]
+        }))
+        .unwrap();
+
+    let document = &result["documents"]["items"][0];
+    assert_eq!(document["@id"], "https://read.readwise.io/read/01abc");
+    assert_eq!(document["@type"], "know:Article");
+    assert_eq!(document["location"], "later");
+    assert_eq!(document["reading_progress"], 0.25);
+    assert_eq!(document["tags"], json!(["AI"]));
+}
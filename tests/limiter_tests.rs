@@ -0,0 +1,108 @@
+// This is free and unencumbered software released into the public domain.
+
+use asimov_readwise_module::api::limiter::{
+    Budget, DEFAULT_ENDPOINT, RateLimiter, RateLimits, ThrottleHook, endpoint_key, parse_rate_limit,
+};
+use std::time::Duration;
+
+#[test]
+fn test_default_budgets_follow_readwise_limits() {
+    let limits = RateLimits::default();
+    let budget = |url| limits.budget_for(url);
+
+    assert_eq!(
+        budget("https://readwise.io/api/v2/highlights/?page=2"),
+        ("/highlights/", Some(Budget::per_minute(20)))
+    );
+    assert_eq!(
+        budget("https://readwise.io/api/v2/export/?pageCursor=abc"),
+        ("/export/", Some(Budget::per_minute(20)))
+    );
+    assert_eq!(
+        budget("https://readwise.io/api/v2/auth/"),
+        (DEFAULT_ENDPOINT, Some(Budget::per_minute(240)))
+    );
+}
+
+#[test]
+fn test_limiter_paces_requests_after_burst() {
+    let mut limits = RateLimits::unlimited();
+    limits.set("/books/", Budget::per_minute(60).with_burst(2));
+    let limiter = RateLimiter::new(limits);
+    let url = "https://readwise.io/api/v2/books/";
+
+    assert_eq!(limiter.reserve(url), Duration::ZERO);
+    assert_eq!(limiter.reserve(url), Duration::ZERO);
+    let wait = limiter.reserve(url);
+    assert!(wait > Duration::from_millis(900) && wait <= Duration::from_secs(1));
+    let wait = limiter.reserve(url);
+    assert!(wait > Duration::from_millis(1900) && wait <= Duration::from_secs(2));
+
+    // Other endpoints have their own budgets.
+    assert_eq!(
+        limiter.reserve("https://readwise.io/api/v2/auth/"),
+        Duration::ZERO
+    );
+}
+
+#[test]
+fn test_parse_rate_limit() {
+    assert_eq!(
+        parse_rate_limit("/highlights/=30"),
+        Ok(("/highlights/".to_string(), Budget::per_minute(30)))
+    );
+    assert_eq!(
+        parse_rate_limit("highlights=30"),
+        Ok(("/highlights/".to_string(), Budget::per_minute(30)))
+    );
+    assert_eq!(
+        parse_rate_limit("default=240"),
+        Ok((DEFAULT_ENDPOINT.to_string(), Budget::per_minute(240)))
+    );
+    assert!(parse_rate_limit("default").is_err());
+    assert!(parse_rate_limit("/=30").is_err());
+    assert!(parse_rate_limit("default=0").is_err());
+    assert!(parse_rate_limit("default=fast").is_err());
+}
+
+#[test]
+fn test_endpoint_keys_are_normalized() {
+    assert_eq!(endpoint_key("/highlights"), "/highlights/");
+    assert_eq!(endpoint_key("highlights/"), "/highlights/");
+    assert_eq!(endpoint_key(" /highlights/ "), "/highlights/");
+    assert_eq!(endpoint_key(DEFAULT_ENDPOINT), DEFAULT_ENDPOINT);
+
+    let mut limits = RateLimits::unlimited();
+    limits.set("/highlights", Budget::per_minute(10));
+    assert_eq!(
+        limits.budget_for("https://readwise.io/api/v2/highlights/?page=2"),
+        ("/highlights/", Some(Budget::per_minute(10)))
+    );
+}
+
+#[test]
+fn test_throttle_hook_is_notified_once_per_endpoint() {
+    use std::sync::{Arc, Mutex};
+
+    let throttled = Arc::new(Mutex::new(Vec::new()));
+    let hook = {
+        let throttled = throttled.clone();
+        ThrottleHook::new(move |endpoint, budget| {
+            throttled
+                .lock()
+                .unwrap()
+                .push((endpoint.to_string(), budget));
+        })
+    };
+    let mut limits = RateLimits::unlimited();
+    limits.set("/books/", Budget::per_minute(60));
+    let limiter = RateLimiter::new(limits).with_throttle_hook(hook);
+
+    for _ in 0..3 {
+        limiter.reserve("https://readwise.io/api/v2/books/");
+    }
+    assert_eq!(
+        *throttled.lock().unwrap(),
+        vec![("/books/".to_string(), Budget::per_minute(60))]
+    );
+}
@@ -0,0 +1,52 @@
+// This is free and unencumbered software released into the public domain.
+
+use asimov_readwise_module::sync::{SyncState, is_later, parse_since};
+
+#[test]
+fn test_parse_since_normalizes_to_utc() {
+    let since = parse_since("2025-01-01T02:00:00+02:00").unwrap();
+    assert_eq!(since, "2025-01-01T00:00:00Z");
+}
+
+#[test]
+fn test_parse_since_rejects_invalid_timestamp() {
+    assert!(parse_since("2025-01-01").is_err());
+}
+
+#[test]
+fn test_is_later_compares_instants() {
+    assert!(is_later("2025-01-02T00:00:00Z", "2025-01-01T00:00:00Z"));
+    assert!(!is_later("2025-01-01T00:00:00Z", "2025-01-01T00:00:00Z"));
+    assert!(!is_later(
+        "2025-01-01T01:00:00+02:00",
+        "2025-01-01T00:00:00Z"
+    ));
+}
+
+#[test]
+fn test_sync_state_missing_file_is_empty() {
+    let path = std::env::temp_dir().join("asimov-readwise-missing-state.json");
+    let _ = std::fs::remove_file(&path);
+    let state = SyncState::load(&path).unwrap();
+    assert!(state.last_synced_at("readwise-highlights").is_none());
+}
+
+#[test]
+fn test_sync_state_round_trip() {
+    let path = std::env::temp_dir()
+        .join("asimov-readwise-state-test")
+        .join("state.json");
+
+    let mut state = SyncState::default();
+    state.set_last_synced_at("readwise-highlights", "2025-01-01T00:00:00Z");
+    state.save(&path).unwrap();
+
+    let loaded = SyncState::load(&path).unwrap();
+    assert_eq!(
+        loaded.last_synced_at("readwise-highlights"),
+        Some("2025-01-01T00:00:00Z")
+    );
+    assert!(loaded.last_synced_at("readwise-booklist").is_none());
+
+    std::fs::remove_file(&path).unwrap();
+}
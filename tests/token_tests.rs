@@ -0,0 +1,91 @@
+// This is free and unencumbered software released into the public domain.
+
+use asimov_module::secrecy::ExposeSecret;
+use asimov_readwise_module::api::token::{TokenSource, resolve_first};
+use std::path::PathBuf;
+
+fn temp_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("asimov-readwise-token-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
+
+#[test]
+fn test_token_file_is_trimmed() {
+    let path = temp_path("token.txt");
+    std::fs::write(&path, "file-token\n").unwrap();
+
+    let token = TokenSource::File(path).resolve().unwrap();
+    assert_eq!(token.expose_secret(), "file-token");
+}
+
+#[test]
+fn test_missing_token_file_is_an_error() {
+    let source = TokenSource::File(temp_path("missing-token.txt"));
+    let error = source.lookup().unwrap_err();
+    assert_eq!(error.kind(), "config");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_token_command() {
+    let token = TokenSource::Command("echo command-token".to_string())
+        .resolve()
+        .unwrap();
+    assert_eq!(token.expose_secret(), "command-token");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_failing_token_command_is_an_error() {
+    let error = TokenSource::Command("exit 3".to_string())
+        .lookup()
+        .unwrap_err();
+    assert!(error.to_string().contains("token command `exit 3` failed"));
+}
+
+#[test]
+fn test_config_file_profile() {
+    let token_file = temp_path("profile-token.txt");
+    std::fs::write(&token_file, "profile-token").unwrap();
+    let config = temp_path("readwise.json");
+    let json = serde_json::json!({
+        "profiles": { "default": { "token_file": token_file } }
+    });
+    std::fs::write(&config, json.to_string()).unwrap();
+
+    let source = TokenSource::Profile {
+        path: config.clone(),
+        name: "default".to_string(),
+    };
+    assert_eq!(source.resolve().unwrap().expose_secret(), "profile-token");
+
+    let missing = TokenSource::Profile {
+        path: config,
+        name: "work".to_string(),
+    };
+    assert!(missing.lookup().unwrap().is_none());
+}
+
+#[test]
+fn test_resolve_first_follows_precedence() {
+    let sources = [
+        TokenSource::Env("ASIMOV_READWISE_TEST_UNSET_VARIABLE".to_string()),
+        TokenSource::Value("first".into()),
+        TokenSource::Value("second".into()),
+    ];
+    assert_eq!(resolve_first(&sources).unwrap().expose_secret(), "first");
+}
+
+#[test]
+fn test_resolve_first_lists_sources_tried() {
+    let sources = [
+        TokenSource::Env("ASIMOV_READWISE_TEST_UNSET_VARIABLE".to_string()),
+        TokenSource::EnvFile("ASIMOV_READWISE_TEST_UNSET_FILE_VARIABLE".to_string()),
+    ];
+    let error = resolve_first(&sources).unwrap_err();
+    assert!(error.to_string().ends_with(
+        "no Readwise access token found (tried $ASIMOV_READWISE_TEST_UNSET_VARIABLE, \
+         the file named by $ASIMOV_READWISE_TEST_UNSET_FILE_VARIABLE)"
+    ));
+}